@@ -0,0 +1,89 @@
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
+
+/// An error encountered while parsing a PE file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PeError {
+    /// The file does not start with the `MZ` DOS signature. This is not a PE file.
+    InvalidDosMagic,
+    /// The NT headers do not start with the `PE\0\0` signature. This is not a PE file.
+    InvalidNtSignature,
+    /// The optional header magic is neither PE32 nor PE32+.
+    UnknownPeMagic(PeMagic),
+    /// The file is PE32 and was parsed as PE32+ (or the other way around), e.g. with [`Pe32`]
+    /// instead of [`AutoDetect`]. Holds the optional header magic of the file.
+    ///
+    /// [`Pe32`]: crate::pe_width::Pe32
+    /// [`AutoDetect`]: crate::pe_width::AutoDetect
+    WidthMismatch(PeMagic),
+    /// The requested data directory is past `number_of_rva_and_sizes` in the optional header.
+    DirectoryOutOfRange(DataDirectoryType),
+    /// The requested data directory is empty, i.e. the file does not have e.g. an export table.
+    MissingDirectory(DataDirectoryType),
+    /// The given RVA does not belong to any section.
+    RvaNotInSection(u32),
+    /// A header or table extends past the end of the data.
+    Truncated,
+    /// A name (of a DLL, section, etc.) is not valid UTF-8.
+    NonUtf8Name,
+    /// The requested export does not exist.
+    ExportNotFound,
+    /// The requested import does not exist.
+    ImportNotFound,
+    /// A forwarded export's forwarder string is not in the `DLL.Function` or `DLL.#Ordinal` format.
+    MalformedForwarder,
+    /// The data is not aligned to at least 4 bytes.
+    Misaligned,
+    /// A base relocation block's size is out of range, or a fixup is missing its extra entry.
+    MalformedRelocation,
+    /// A base relocation has a type which cannot be applied.
+    UnsupportedRelocation(RelocationType),
+    /// An image base does not fit the file's format, i.e. is above 4 GiB for PE32.
+    ImageBaseOutOfRange(u64),
+    /// The requested resource does not exist.
+    ResourceNotFound,
+    /// A resource's data is not in the format expected for its type.
+    MalformedResource,
+    /// The file has no debug information of the requested type.
+    DebugInfoNotFound,
+    /// A debug directory entry's data is not in the format expected for its type.
+    MalformedDebugInfo,
+    /// Two slices passed together (e.g. names and their results) have different lengths.
+    LengthMismatch,
+}
+
+impl Display for PeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PeError::InvalidDosMagic => write!(f, "invalid DOS header magic, expected 'MZ'"),
+            PeError::InvalidNtSignature => write!(f, "invalid NT headers signature, expected 'PE'"),
+            PeError::UnknownPeMagic(magic) => write!(f, "unknown optional header magic 0x{:X}", magic.value()),
+            PeError::WidthMismatch(magic) => {
+                write!(
+                    f,
+                    "optional header magic 0x{:X} does not match the requested PE width",
+                    magic.value()
+                )
+            }
+            PeError::DirectoryOutOfRange(directory) => write!(f, "data directory {:?} is out of range", directory),
+            PeError::MissingDirectory(directory) => write!(f, "data directory {:?} is not present", directory),
+            PeError::RvaNotInSection(rva) => write!(f, "RVA 0x{:X} does not belong to any section", rva),
+            PeError::Truncated => write!(f, "header or table extends past the end of the data"),
+            PeError::NonUtf8Name => write!(f, "name is not valid UTF-8"),
+            PeError::ExportNotFound => write!(f, "export not found"),
+            PeError::ImportNotFound => write!(f, "import not found"),
+            PeError::MalformedForwarder => write!(f, "malformed export forwarder string"),
+            PeError::Misaligned => write!(f, "data is not aligned to 4 bytes"),
+            PeError::MalformedRelocation => write!(f, "malformed base relocation block"),
+            PeError::UnsupportedRelocation(ty) => write!(f, "unsupported base relocation type {}", ty.value()),
+            PeError::ImageBaseOutOfRange(base) => write!(f, "image base 0x{:X} is out of range", base),
+            PeError::ResourceNotFound => write!(f, "resource not found"),
+            PeError::MalformedResource => write!(f, "malformed resource data"),
+            PeError::DebugInfoNotFound => write!(f, "debug information not found"),
+            PeError::MalformedDebugInfo => write!(f, "malformed debug information"),
+            PeError::LengthMismatch => write!(f, "slices have different lengths"),
+        }
+    }
+}
+
+impl core::error::Error for PeError {}
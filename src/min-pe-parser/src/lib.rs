@@ -1,5 +1,5 @@
 #![doc = include_str!(concat!("../", env!("CARGO_PKG_README")))]
-#![feature(optimize_attribute)]
+#![cfg_attr(feature = "size_opt", feature(optimize_attribute))]
 #![no_std]
 
 #[cfg(feature = "std")]
@@ -8,21 +8,46 @@ extern crate std;
 extern crate alloc;
 
 pub mod utils {
+    pub mod aligned_buffer;
+    pub mod apply_relocations;
     pub(crate) mod common;
+    pub mod get_bound_imports;
+    pub mod get_debug_directories;
+    pub mod get_delay_imports;
     pub mod get_export_rva;
+    pub mod get_export_rva_by_hash;
+    pub mod get_export_rvas;
+    pub mod get_exports;
+    pub mod get_icon;
     pub mod get_import_dll_names;
+    pub mod get_imports;
+    pub mod get_manifest;
+    pub mod get_message;
+    pub mod get_relocations;
+    pub mod get_resource_string;
+    pub mod get_resources;
     pub mod get_section_names;
+    pub mod get_version_info;
+    pub mod iat_hook;
+    pub mod map_image;
     #[cfg(test)]
     #[allow(unused_attributes)]
     #[cfg_attr(tarpaulin, ignore)]
     pub mod test_utils;
+    pub mod unmap_image;
 }
 
 #[cfg(not(tarpaulin_include))]
 #[allow(non_snake_case)]
 #[allow(non_camel_case_types)]
 pub mod structs {
+    pub mod GUID;
+    pub mod IMAGE_BASE_RELOCATION;
+    pub mod IMAGE_BOUND_FORWARDER_REF;
+    pub mod IMAGE_BOUND_IMPORT_DESCRIPTOR;
     pub mod IMAGE_DATA_DIRECTORY;
+    pub mod IMAGE_DEBUG_DIRECTORY;
+    pub mod IMAGE_DELAYLOAD_DESCRIPTOR;
     pub mod IMAGE_DOS_HEADER;
     pub mod IMAGE_EXPORT_DIRECTORY;
     pub mod IMAGE_FILE_HEADER;
@@ -30,21 +55,38 @@ pub mod structs {
     pub mod IMAGE_IMPORT_DESCRIPTOR;
     pub mod IMAGE_OPTIONAL_HEADER32;
     pub mod IMAGE_OPTIONAL_HEADER64;
+    pub mod IMAGE_RESOURCE_DATA_ENTRY;
+    pub mod IMAGE_RESOURCE_DIRECTORY;
+    pub mod IMAGE_RESOURCE_DIRECTORY_ENTRY;
     pub mod IMAGE_SECTION_HEADER;
     pub mod IMAGE_THUNK_DATA32;
     pub mod IMAGE_THUNK_DATA64;
+    pub mod VS_FIXEDFILEINFO;
 
     pub mod enums {
         pub mod data_directory_type;
         pub mod data_section_flags;
+        pub mod debug_type;
         pub mod pe_magic;
+        pub mod relocation_type;
+        pub mod resource_type;
+        pub mod thunk;
     }
 }
 
 pub mod prelude {
     pub use crate::structs::{
-        enums::{data_directory_type::*, data_section_flags::*, pe_magic::*},
+        enums::{
+            data_directory_type::*, data_section_flags::*, debug_type::*, pe_magic::*, relocation_type::*,
+            resource_type::*, thunk::*,
+        },
+        GUID::*,
+        IMAGE_BASE_RELOCATION::*,
+        IMAGE_BOUND_FORWARDER_REF::*,
+        IMAGE_BOUND_IMPORT_DESCRIPTOR::*,
         IMAGE_DATA_DIRECTORY::*,
+        IMAGE_DEBUG_DIRECTORY::*,
+        IMAGE_DELAYLOAD_DESCRIPTOR::*,
         IMAGE_DOS_HEADER::*,
         IMAGE_EXPORT_DIRECTORY::*,
         IMAGE_FILE_HEADER::*,
@@ -52,11 +94,22 @@ pub mod prelude {
         IMAGE_IMPORT_DESCRIPTOR::*,
         IMAGE_OPTIONAL_HEADER32::*,
         IMAGE_OPTIONAL_HEADER64::*,
+        IMAGE_RESOURCE_DATA_ENTRY::*,
+        IMAGE_RESOURCE_DIRECTORY::*,
+        IMAGE_RESOURCE_DIRECTORY_ENTRY::*,
         IMAGE_SECTION_HEADER::*,
         IMAGE_THUNK_DATA32::*,
         IMAGE_THUNK_DATA64::*,
+        VS_FIXEDFILEINFO::*,
     };
 }
 
+pub mod error;
+pub mod name_or_id;
+pub mod pe_headers;
+pub mod pe_view;
+pub mod pe_width;
+pub mod utf16_str;
+
 #[cfg(not(tarpaulin_include))]
 pub(crate) mod types;
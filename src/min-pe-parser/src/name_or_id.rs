@@ -0,0 +1,11 @@
+/// Something which is referred to either by name or by a 16-bit ID.
+///
+/// This is e.g. an import or export (where the ID is the ordinal), or a resource type or name.
+/// `N` is whatever represents the name, such as a `&str` when searching for it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NameOrId<N> {
+    /// Referred to by name.
+    Name(N),
+    /// Referred to by ID (or ordinal).
+    Id(u16),
+}
@@ -0,0 +1,433 @@
+use crate::{
+    error::PeError,
+    name_or_id::NameOrId,
+    pe_width::{AutoDetect, KnownPeWidth, PeWidth},
+    prelude::*,
+    types::*,
+    utils::common::*,
+};
+use core::{
+    ffi::{c_char, c_void, CStr},
+    marker::PhantomData,
+    mem::{align_of, size_of, size_of_val},
+    ptr::read_unaligned,
+    slice,
+};
+use simple_endian::LittleEndian;
+
+/// Size of the `PE\0\0` signature preceding the [`IMAGE_FILE_HEADER`].
+const NT_SIGNATURE_SIZE: usize = size_of::<u32>();
+
+/// The headers of a PE file, parsed once and shared by all queries.
+///
+/// This captures the PE32/PE64 decision, the data directories and the section headers,
+/// so looking up e.g. many exports does not walk the DOS and NT headers each time.
+///
+/// `W` decides whether the file is treated as PE32 or PE32+, see [`PeWidth`].
+///
+/// When `CHECKED` is `false` (the default), the file is trusted completely and reads
+/// compile down to plain pointer accesses. When `CHECKED` is `true` (see [`PeView`]),
+/// every read is bounds checked against the length of the file.
+///
+/// [`PeView`]: crate::pe_view::PeView
+#[derive(Copy, Clone, Debug)]
+pub struct PeHeaders<'a, W = AutoDetect, const CHECKED: bool = false> {
+    pub(crate) pe_start: *const u8,
+    pub(crate) len: usize,
+    is_mapped: bool,
+    is_pe64: bool,
+    optional_header_offset: usize,
+    data_directories: &'a [IMAGE_DATA_DIRECTORY],
+    section_headers: &'a [IMAGE_SECTION_HEADER],
+    _data: PhantomData<(&'a [u8], W)>,
+}
+
+impl<'a, W: PeWidth> PeHeaders<'a, W, false> {
+    /// Parses the headers of the PE file at the given address.
+    ///
+    /// # Arguments
+    /// - `pe_start` - A pointer to the start of the PE file in memory.
+    /// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+    ///
+    /// # Remarks
+    /// If `W` is [`Pe32`] or [`Pe64`], the file is assumed to be in that format without checking.
+    ///
+    /// [`Pe32`]: crate::pe_width::Pe32
+    /// [`Pe64`]: crate::pe_width::Pe64
+    ///
+    /// # Safety
+    /// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+    /// The PE file must remain valid for the lifetime `'a`.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub unsafe fn from_ptr(pe_start: *const c_void, is_mapped: bool) -> Result<Self, PeError> {
+        assert_pe_is_aligned(pe_start);
+        Self::parse(pe_start as pu8, usize::MAX, is_mapped)
+    }
+}
+
+impl<'a, W: KnownPeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Returns the optional header.
+    pub fn optional_header(&self) -> &'a W::OptionalHeader {
+        // SAFETY: Validated on creation. The struct is packed, so there are no alignment requirements.
+        unsafe { &*(self.pe_start.add(self.optional_header_offset) as *const W::OptionalHeader) }
+    }
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Parses the headers. `len` is only used if `CHECKED` is set.
+    ///
+    /// # Safety
+    /// `pe_start` must be valid for `len` bytes if `CHECKED` is set, else it must point to a valid PE file.
+    pub(crate) unsafe fn parse(pe_start: pu8, len: usize, is_mapped: bool) -> Result<Self, PeError> {
+        let mut headers = Self {
+            pe_start,
+            len,
+            is_mapped,
+            is_pe64: false,
+            optional_header_offset: 0,
+            data_directories: &[],
+            section_headers: &[],
+            _data: PhantomData,
+        };
+
+        // Validate PE file signature.
+        let dos_header: IMAGE_DOS_HEADER = headers.read(0)?;
+        if dos_header.e_magic() != IMAGE_DOS_SIGNATURE {
+            return Err(PeError::InvalidDosMagic);
+        }
+
+        // Get the NT Header.
+        let nt_headers_offset = dos_header.e_lfanew() as usize;
+        if u32::from(headers.read::<LittleEndian<u32>>(nt_headers_offset)?) != IMAGE_NT_SIGNATURE {
+            return Err(PeError::InvalidNtSignature);
+        }
+
+        // Access the IMAGE_FILE_HEADER to retrieve the number of sections and other metadata.
+        let file_header: IMAGE_FILE_HEADER = headers.read(nt_headers_offset + NT_SIGNATURE_SIZE)?;
+        let optional_header_offset = nt_headers_offset + NT_SIGNATURE_SIZE + size_of::<IMAGE_FILE_HEADER>();
+
+        // Determine the actual PE format (PE32 or PE64).
+        // If it's given at the type level, trust it, unless we're checking everything.
+        let is_pe64 = match W::IS_PE64 {
+            Some(is_pe64) if !CHECKED => is_pe64,
+            _ => {
+                let magic: PeMagic = headers.read::<LittleEndian<u16>>(optional_header_offset)?.into();
+                let is_pe64 = magic.is_pe64();
+                if !is_pe64 && !magic.is_pe32() {
+                    return Err(PeError::UnknownPeMagic(magic));
+                }
+
+                if W::IS_PE64.is_some_and(|x| x != is_pe64) {
+                    return Err(PeError::WidthMismatch(magic));
+                }
+
+                is_pe64
+            }
+        };
+
+        // Ensure the whole optional header is present before we read from it.
+        let optional_header_size = if is_pe64 {
+            size_of::<IMAGE_OPTIONAL_HEADER64>()
+        } else {
+            size_of::<IMAGE_OPTIONAL_HEADER32>()
+        };
+        headers.bytes(optional_header_offset, optional_header_size)?;
+
+        // Calculate the number of data directories, and locate them after the optional header.
+        let optional_header_ptr = pe_start.add(optional_header_offset) as PIMAGE_OPTIONAL_HEADER32;
+        let num_rva_sizes = get_num_rva_and_sizes(optional_header_ptr, is_pe64) as usize;
+        let data_directories_ptr = get_data_directories_ptr(optional_header_ptr as pu8, is_pe64);
+        let data_directories_offset = data_directories_ptr as usize - pe_start as usize;
+        let data_directories = headers.slice::<IMAGE_DATA_DIRECTORY>(data_directories_offset, num_rva_sizes)?;
+
+        // The section headers follow the data directories.
+        let section_headers_offset = data_directories_offset + size_of_val(data_directories);
+        let section_headers =
+            headers.slice::<IMAGE_SECTION_HEADER>(section_headers_offset, file_header.number_of_sections() as usize)?;
+
+        headers.is_pe64 = is_pe64;
+        headers.optional_header_offset = optional_header_offset;
+        headers.data_directories = data_directories;
+        headers.section_headers = section_headers;
+        Ok(headers)
+    }
+
+    /// Returns whether the PE file is mapped into memory (image layout), rather than the file layout.
+    pub fn is_mapped(&self) -> bool {
+        self.is_mapped
+    }
+
+    /// Returns whether the file is PE32+ (PE64).
+    #[inline]
+    pub fn is_pe64(&self) -> bool {
+        W::IS_PE64.unwrap_or(self.is_pe64)
+    }
+
+    /// Returns a pointer to the start of the PE file.
+    pub fn pe_start(&self) -> *const u8 {
+        self.pe_start
+    }
+
+    /// Returns the file header.
+    pub fn file_header(&self) -> &'a IMAGE_FILE_HEADER {
+        // SAFETY: Validated on creation. The struct is packed, so there are no alignment requirements.
+        unsafe {
+            &*(self
+                .pe_start
+                .add(self.optional_header_offset - size_of::<IMAGE_FILE_HEADER>()) as PIMAGE_FILE_HEADER)
+        }
+    }
+
+    /// Returns the offset of the optional header from the start of the file.
+    pub fn optional_header_offset(&self) -> usize {
+        self.optional_header_offset
+    }
+
+    /// Returns the optional header, if the file is PE32.
+    pub fn optional_header32(&self) -> Option<&'a IMAGE_OPTIONAL_HEADER32> {
+        // SAFETY: Validated on creation. The struct is packed, so there are no alignment requirements.
+        (!self.is_pe64())
+            .then(|| unsafe { &*(self.pe_start.add(self.optional_header_offset) as PIMAGE_OPTIONAL_HEADER32) })
+    }
+
+    /// Returns the optional header, if the file is PE32+ (PE64).
+    pub fn optional_header64(&self) -> Option<&'a IMAGE_OPTIONAL_HEADER64> {
+        // SAFETY: Validated on creation. The struct is packed, so there are no alignment requirements.
+        self.is_pe64()
+            .then(|| unsafe { &*(self.pe_start.add(self.optional_header_offset) as PIMAGE_OPTIONAL_HEADER64) })
+    }
+
+    /// Reads a field of the optional header, with `pe64` for PE32+ and `pe32` for PE32.
+    #[inline]
+    fn optional_field<T>(
+        &self,
+        pe64: impl FnOnce(&IMAGE_OPTIONAL_HEADER64) -> T,
+        pe32: impl FnOnce(&IMAGE_OPTIONAL_HEADER32) -> T,
+    ) -> T {
+        // SAFETY: Validated on creation. The structs are packed, so there are no alignment requirements.
+        let optional_header = unsafe { self.pe_start.add(self.optional_header_offset) };
+        if self.is_pe64() {
+            pe64(unsafe { &*(optional_header as PIMAGE_OPTIONAL_HEADER64) })
+        } else {
+            pe32(unsafe { &*(optional_header as PIMAGE_OPTIONAL_HEADER32) })
+        }
+    }
+
+    /// Returns the preferred address of the image, from the optional header.
+    pub fn image_base(&self) -> u64 {
+        self.optional_field(|x| x.image_base(), |x| x.image_base().into())
+    }
+
+    /// Returns the combined size of the headers (and section table), from the optional header.
+    pub fn size_of_headers(&self) -> u32 {
+        self.optional_field(|x| x.size_of_headers(), |x| x.size_of_headers())
+    }
+
+    /// Returns the size of the image once mapped into memory, from the optional header.
+    pub fn size_of_image(&self) -> u32 {
+        self.optional_field(|x| x.size_of_image(), |x| x.size_of_image())
+    }
+
+    /// Returns the alignment of the raw data of sections in the file, from the optional header.
+    pub fn file_alignment(&self) -> u32 {
+        self.optional_field(|x| x.file_alignment(), |x| x.file_alignment())
+    }
+
+    /// Returns the data directories.
+    pub fn data_directories(&self) -> &'a [IMAGE_DATA_DIRECTORY] {
+        self.data_directories
+    }
+
+    /// Returns the section headers.
+    pub fn section_headers(&self) -> &'a [IMAGE_SECTION_HEADER] {
+        self.section_headers
+    }
+
+    /// Returns the given data directory.
+    ///
+    /// # Returns
+    /// [`PeError::DirectoryOutOfRange`] if the file does not have that many directories.
+    pub fn data_directory(&self, directory: DataDirectoryType) -> Result<IMAGE_DATA_DIRECTORY, PeError> {
+        self.data_directories
+            .get(directory as usize)
+            .copied()
+            .ok_or(PeError::DirectoryOutOfRange(directory))
+    }
+
+    /// Returns the offset of the given data directory's contents from the start of the file, and its size.
+    ///
+    /// # Returns
+    /// [`PeError::MissingDirectory`] if the directory is empty.
+    pub fn data_directory_offset(&self, directory: DataDirectoryType) -> Result<(usize, u32), PeError> {
+        let data_directory = self.data_directory(directory)?;
+        if data_directory.virtual_address() == 0 {
+            return Err(PeError::MissingDirectory(directory));
+        }
+
+        Ok((
+            self.rva_to_offset(data_directory.virtual_address())?,
+            data_directory.size(),
+        ))
+    }
+
+    /// Like [`PeHeaders::data_directory_offset`], but returns [`None`] if the file does not
+    /// have the directory, so callers can return an empty result rather than an error.
+    pub(crate) fn data_directory_offset_if_present(
+        &self,
+        directory: DataDirectoryType,
+    ) -> Result<Option<(usize, u32)>, PeError> {
+        match self.data_directory_offset(directory) {
+            Ok(x) => Ok(Some(x)),
+            Err(PeError::MissingDirectory(_) | PeError::DirectoryOutOfRange(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Converts an RVA to an offset from the start of the file.
+    ///
+    /// # Returns
+    /// [`PeError::RvaNotInSection`] if the RVA is 0, or does not belong to any section in a file layout.
+    #[inline]
+    pub fn rva_to_offset(&self, rva: u32) -> Result<usize, PeError> {
+        // SAFETY: The section headers were validated on creation.
+        unsafe { try_rva_to_absolute_offset(rva, self.section_headers, self.is_mapped) }.map(|x| x as usize)
+    }
+
+    /// Ensures `size` bytes at `offset` are within the file, and returns the address of `offset`.
+    #[inline]
+    pub(crate) fn ptr(&self, offset: usize, size: usize) -> Result<pu8, PeError> {
+        if CHECKED && offset.checked_add(size).is_none_or(|end| end > self.len) {
+            return Err(PeError::Truncated);
+        }
+
+        // SAFETY: Checked above, or the file is trusted.
+        Ok(unsafe { self.pe_start.add(offset) })
+    }
+
+    /// Reads a (potentially unaligned) value of type `T` at the given offset.
+    ///
+    /// `T` must be a plain data type which is valid for any bit pattern, i.e. one of the structs.
+    #[inline]
+    pub(crate) fn read<T: Copy>(&self, offset: usize) -> Result<T, PeError> {
+        let ptr = self.ptr(offset, size_of::<T>())?;
+        // SAFETY: In bounds, and the read is unaligned.
+        Ok(unsafe { read_unaligned(ptr as *const T) })
+    }
+
+    /// Returns `size` bytes at the given offset.
+    #[inline]
+    pub(crate) fn bytes(&self, offset: usize, size: usize) -> Result<&'a [u8], PeError> {
+        let ptr = self.ptr(offset, size)?;
+        // SAFETY: In bounds.
+        Ok(unsafe { slice::from_raw_parts(ptr, size) })
+    }
+
+    /// Returns a slice of `count` items of type `T` at the given offset.
+    ///
+    /// In checked mode the slice must be suitably aligned, else [`PeError::Misaligned`] is returned.
+    #[inline]
+    pub(crate) fn slice<T>(&self, offset: usize, count: usize) -> Result<&'a [T], PeError> {
+        let size = count.checked_mul(size_of::<T>()).ok_or(PeError::Truncated)?;
+        let ptr = self.ptr(offset, size)?;
+        if CHECKED && !(ptr as usize).is_multiple_of(align_of::<T>()) {
+            return Err(PeError::Misaligned);
+        }
+
+        // WARNING !!
+        //   In unchecked mode, we trust that the struct members are aligned, as with the
+        //   original raw pointer code.
+        Ok(unsafe { slice::from_raw_parts(ptr as *const T, count) })
+    }
+
+    /// Returns the bytes of the null terminated string at the given offset, excluding the terminator.
+    #[inline]
+    pub(crate) fn c_str(&self, offset: usize) -> Result<&'a [u8], PeError> {
+        if CHECKED {
+            let remaining = self.bytes(offset, self.len.saturating_sub(offset))?;
+            let len = remaining.iter().position(|&x| x == 0).ok_or(PeError::Truncated)?;
+            return Ok(&remaining[..len]);
+        }
+
+        // SAFETY: The file is trusted, so the string is null terminated.
+        Ok(unsafe { CStr::from_ptr(self.pe_start.add(offset) as *const c_char) }.to_bytes())
+    }
+
+    /// Returns the size of an [`IMAGE_THUNK_DATA32`] or [`IMAGE_THUNK_DATA64`], depending on the format.
+    #[inline]
+    pub(crate) fn thunk_size(&self) -> usize {
+        if self.is_pe64() {
+            size_of::<IMAGE_THUNK_DATA64>()
+        } else {
+            size_of::<IMAGE_THUNK_DATA32>()
+        }
+    }
+
+    /// Reads and decodes the thunk at the given offset of an import lookup (or name) table,
+    /// see [`Thunk::decode_lookup`]. Returns [`None`] for the null thunk terminating the table.
+    #[inline]
+    pub(crate) fn lookup_thunk(&self, offset: usize) -> Result<Option<NameOrId<u32>>, PeError> {
+        let (value, ordinal_flag) = if self.is_pe64() {
+            (self.read::<IMAGE_THUNK_DATA64>(offset)?.value(), IMAGE_ORDINAL_FLAG64)
+        } else {
+            let value = self.read::<IMAGE_THUNK_DATA32>(offset)?.value();
+            (value.into(), IMAGE_ORDINAL_FLAG32.into())
+        };
+
+        Ok((value != 0).then(|| Thunk::decode_lookup(value, ordinal_flag)))
+    }
+
+    /// Returns the null terminated UTF-8 string at the given offset, excluding the terminator.
+    #[inline]
+    pub(crate) fn utf8_str(&self, offset: usize) -> Result<&'a str, PeError> {
+        core::str::from_utf8(self.c_str(offset)?).map_err(|_| PeError::NonUtf8Name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pe_width::Pe32, utils::test_utils::*};
+
+    #[test]
+    fn test_pe_headers_reused_across_queries_x64() {
+        let headers = unsafe {
+            PeHeaders::<AutoDetect>::from_ptr(RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void, false)
+        }
+        .unwrap();
+
+        assert!(headers.is_pe64());
+        assert!(headers.optional_header32().is_none());
+        assert_eq!(headers.optional_header64().unwrap().size_of_headers(), 0x400);
+        assert_eq!(headers.file_header().number_of_sections(), 6);
+        assert_eq!(headers.section_headers().len(), 6);
+        assert_eq!(headers.data_directories().len(), 16);
+
+        assert_eq!(headers.export_rva("InitializeASI"), Ok(0x236c));
+        assert_eq!(headers.export_rva("SonicRModInfo"), Ok(0x240c0));
+        assert_eq!(headers.import_dll_names().unwrap().len(), 16);
+        assert_eq!(headers.section_names().unwrap()[0], ".text");
+    }
+
+    #[test]
+    fn test_pe_headers_x86() {
+        let headers =
+            unsafe { PeHeaders::<Pe32>::from_ptr(RELOADED_BOOTSTRAPPER_DLL_X86.as_ptr() as *const c_void, false) }
+                .unwrap();
+
+        assert!(!headers.is_pe64());
+        assert_eq!(headers.optional_header().image_base(), 0x10000000);
+        assert_eq!(headers.image_base(), 0x10000000);
+        assert_eq!(headers.size_of_headers(), 0x400);
+        assert_eq!(headers.size_of_image(), 0x20000);
+        assert_eq!(headers.file_alignment(), 0x200);
+        assert_eq!(headers.rva_to_offset(0x1a050), Ok(0x18e50));
+        assert_eq!(headers.rva_to_offset(0x1), Err(PeError::RvaNotInSection(0x1)));
+        assert_eq!(
+            headers.data_directory(DataDirectoryType::Reserved).unwrap(),
+            IMAGE_DATA_DIRECTORY {
+                virtual_address: 0.into(),
+                size: 0.into()
+            }
+        );
+    }
+}
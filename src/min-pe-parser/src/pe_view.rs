@@ -0,0 +1,147 @@
+use crate::{
+    error::PeError,
+    pe_headers::PeHeaders,
+    pe_width::{AutoDetect, PeWidth},
+};
+use core::slice;
+
+/// A bounds-checked view over a PE file stored in a byte slice.
+///
+/// All of the header ranges (DOS header, NT headers, optional header, data directories
+/// and section headers) are validated once in [`PeView::new`]. Every table reached
+/// through an RVA is then bounds-checked as it is read, so a truncated or malformed
+/// file results in a [`PeError`] rather than undefined behaviour.
+///
+/// This is the safe counterpart to the raw pointer functions in [`crate::utils`],
+/// which remain the zero-overhead path for trusted input. All of the queries are
+/// shared with [`PeHeaders`].
+pub type PeView<'a> = PeHeaders<'a, AutoDetect, true>;
+
+impl<'a, W: PeWidth> PeHeaders<'a, W, true> {
+    /// Validates the headers of the PE file in `data` and creates a view over it.
+    ///
+    /// # Arguments
+    /// - `data` - The PE file.
+    /// - `is_mapped` - Whether `data` is in the mapped (image) layout rather than the file layout.
+    ///
+    /// # Remarks
+    /// If `W` is [`Pe32`] or [`Pe64`], files in the other format are rejected with
+    /// [`PeError::WidthMismatch`].
+    ///
+    /// The data directories and section headers are read in place, so they must be aligned
+    /// to 4 bytes, else [`PeError::Misaligned`] is returned. This is always the case if `data`
+    /// is 4 byte aligned and the file is well formed.
+    ///
+    /// [`Pe32`]: crate::pe_width::Pe32
+    /// [`Pe64`]: crate::pe_width::Pe64
+    pub fn new(data: &'a [u8], is_mapped: bool) -> Result<Self, PeError> {
+        // SAFETY: `data` is valid for `data.len()` bytes, and all reads are checked against that.
+        unsafe { Self::parse(data.as_ptr(), data.len(), is_mapped) }
+    }
+
+    /// Returns the underlying bytes of the PE file.
+    pub fn data(&self) -> &'a [u8] {
+        // SAFETY: Created from a slice in `new`.
+        unsafe { slice::from_raw_parts(self.pe_start, self.len) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_width::{Pe32, Pe64},
+        prelude::{PeMagic, IMAGE_DOS_HEADER},
+        utils::{aligned_buffer::AlignedBuffer, test_utils::*},
+    };
+    use alloc::vec;
+    use core::ptr::read_unaligned;
+
+    #[test]
+    fn test_pe_view_matches_raw_parser_x64() {
+        let view = PeView::new(RELOADED_BOOTSTRAPPER_DLL_X64, false).unwrap();
+        assert!(view.is_pe64());
+
+        assert_eq!(view.export_rva("InitializeASI"), Ok(0x236c));
+        assert_eq!(view.export_rva("get_hostfxr_path"), Ok(0x10940));
+        assert_eq!(view.export_rva("DoesNotExist"), Err(PeError::ExportNotFound));
+        assert_eq!(view.import_dll_names().unwrap().len(), 16);
+        assert_eq!(
+            view.section_names().unwrap(),
+            vec![".text", ".rdata", ".data", ".pdata", ".rsrc", ".reloc"]
+        );
+    }
+
+    #[test]
+    fn test_pe_view_matches_raw_parser_x86() {
+        let view = PeView::new(RELOADED_BOOTSTRAPPER_DLL_X86, false).unwrap();
+        assert!(!view.is_pe64());
+
+        assert_eq!(view.export_rva("InitializeASI"), Ok(0x1caa));
+        assert_eq!(view.export_rva("_get_hostfxr_path@12"), Ok(0xb1b0));
+        assert_eq!(view.import_dll_names().unwrap()[0], "KERNEL32.dll");
+        assert_eq!(
+            view.section_names().unwrap(),
+            vec![".text", ".rdata", ".data", ".rsrc", ".reloc"]
+        );
+    }
+
+    #[test]
+    fn test_pe_view_rejects_invalid_headers() {
+        assert_eq!(
+            PeView::new(&RELOADED_BOOTSTRAPPER_DLL_X64[..0], false).unwrap_err(),
+            PeError::Truncated
+        );
+        assert_eq!(
+            PeView::new(&RELOADED_BOOTSTRAPPER_DLL_X64[..0x100], false).unwrap_err(),
+            PeError::Truncated
+        );
+        // Corrupt the NT signature.
+        let mut bytes = AlignedBuffer::from(RELOADED_BOOTSTRAPPER_DLL_X64);
+        let dos_header = unsafe { read_unaligned(bytes.as_ptr() as *const IMAGE_DOS_HEADER) };
+        bytes[dos_header.e_lfanew() as usize] = b'X';
+        assert_eq!(PeView::new(&bytes, false).unwrap_err(), PeError::InvalidNtSignature);
+
+        bytes[0] = b'X';
+        assert_eq!(PeView::new(&bytes, false).unwrap_err(), PeError::InvalidDosMagic);
+    }
+
+    #[test]
+    fn test_pe_view_rejects_width_mismatch() {
+        assert_eq!(
+            PeHeaders::<Pe32, true>::new(RELOADED_BOOTSTRAPPER_DLL_X64, false).unwrap_err(),
+            PeError::WidthMismatch(PeMagic::PE64)
+        );
+        assert_eq!(
+            PeHeaders::<Pe64, true>::new(RELOADED_BOOTSTRAPPER_DLL_X86, false).unwrap_err(),
+            PeError::WidthMismatch(PeMagic::PE32)
+        );
+    }
+
+    #[test]
+    fn test_pe_view_rejects_misaligned_section_headers() {
+        let mut misaligned = vec![0u8; RELOADED_BOOTSTRAPPER_DLL_X64.len() + 1];
+        let offset = if (misaligned.as_ptr() as usize).is_multiple_of(2) {
+            1
+        } else {
+            0
+        };
+        let bytes = &mut misaligned[offset..offset + RELOADED_BOOTSTRAPPER_DLL_X64.len()];
+        bytes.copy_from_slice(RELOADED_BOOTSTRAPPER_DLL_X64);
+
+        assert_eq!(PeView::new(bytes, false).unwrap_err(), PeError::Misaligned);
+    }
+
+    #[test]
+    fn test_pe_view_truncated_file_does_not_panic() {
+        for len in (0..RELOADED_BOOTSTRAPPER_DLL_X64.len()).step_by(0x80) {
+            if let Ok(view) = PeView::new(&RELOADED_BOOTSTRAPPER_DLL_X64[..len], false) {
+                let _ = view.export_rva("InitializeASI");
+                let _ = view.import_dll_names();
+                let _ = view.imports();
+                let _ = view.exports().map(|x| x.count());
+                let _ = view.section_names();
+            }
+        }
+    }
+}
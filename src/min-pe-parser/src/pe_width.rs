@@ -0,0 +1,62 @@
+use crate::prelude::*;
+use core::fmt::Debug;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// The format (PE32 or PE32+) a PE file is parsed as, decided at the type level.
+///
+/// Use [`Pe32`] or [`Pe64`] if you will only ever deal with one format; this turns
+/// the format checks into constants at compile time, saving a few branches.
+/// Use [`AutoDetect`] to read the format from the optional header at runtime.
+///
+/// This is the `W` parameter of [`PeHeaders`] and of the raw pointer functions in [`crate::utils`].
+///
+/// [`PeHeaders`]: crate::pe_headers::PeHeaders
+pub trait PeWidth: private::Sealed + Copy + Debug {
+    /// `Some(true)` for PE32+, `Some(false)` for PE32, [`None`] to detect at runtime.
+    const IS_PE64: Option<bool>;
+}
+
+/// A [`PeWidth`] which is known at compile time.
+pub trait KnownPeWidth: PeWidth {
+    /// The optional header for this format.
+    type OptionalHeader;
+}
+
+/// Parse the file as PE32.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Pe32;
+
+/// Parse the file as PE32+ (PE64).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Pe64;
+
+/// Detect whether the file is PE32 or PE32+ from the optional header at runtime.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AutoDetect;
+
+impl private::Sealed for Pe32 {}
+impl private::Sealed for Pe64 {}
+impl private::Sealed for AutoDetect {}
+
+impl PeWidth for Pe32 {
+    const IS_PE64: Option<bool> = Some(false);
+}
+
+impl PeWidth for Pe64 {
+    const IS_PE64: Option<bool> = Some(true);
+}
+
+impl PeWidth for AutoDetect {
+    const IS_PE64: Option<bool> = None;
+}
+
+impl KnownPeWidth for Pe32 {
+    type OptionalHeader = IMAGE_OPTIONAL_HEADER32;
+}
+
+impl KnownPeWidth for Pe64 {
+    type OptionalHeader = IMAGE_OPTIONAL_HEADER64;
+}
@@ -0,0 +1,79 @@
+use core::fmt::{Display, Formatter};
+use simple_endian::LittleEndian;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GUID {
+    /// First 8 hex digits
+    pub data1: LittleEndian<u32>,
+    /// Second group of 4 hex digits
+    pub data2: LittleEndian<u16>,
+    /// Third group of 4 hex digits
+    pub data3: LittleEndian<u16>,
+    /// Last 16 hex digits, stored as bytes
+    pub data4: [u8; 8],
+}
+
+impl GUID {
+    /// Returns the first part.
+    pub fn data1(&self) -> u32 {
+        self.data1.into()
+    }
+
+    /// Sets the first part.
+    pub fn set_data1(&mut self, value: u32) {
+        self.data1 = value.into();
+    }
+
+    /// Returns the second part.
+    pub fn data2(&self) -> u16 {
+        self.data2.into()
+    }
+
+    /// Sets the second part.
+    pub fn set_data2(&mut self, value: u16) {
+        self.data2 = value.into();
+    }
+
+    /// Returns the third part.
+    pub fn data3(&self) -> u16 {
+        self.data3.into()
+    }
+
+    /// Sets the third part.
+    pub fn set_data3(&mut self, value: u16) {
+        self.data3 = value.into();
+    }
+
+    /// Returns the last part.
+    pub fn data4(&self) -> [u8; 8] {
+        self.data4
+    }
+
+    /// Sets the last part.
+    pub fn set_data4(&mut self, value: [u8; 8]) {
+        self.data4 = value;
+    }
+}
+
+impl Display for GUID {
+    /// Formats the GUID in the registry format without braces, e.g. `DDD32107-0AD1-939A-1853-64D173A6D69B`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let d = self.data4;
+        write!(
+            f,
+            "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+            self.data1(),
+            self.data2(),
+            self.data3(),
+            d[0],
+            d[1],
+            d[2],
+            d[3],
+            d[4],
+            d[5],
+            d[6],
+            d[7]
+        )
+    }
+}
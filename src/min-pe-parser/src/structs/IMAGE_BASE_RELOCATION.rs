@@ -0,0 +1,32 @@
+use simple_endian::LittleEndian;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IMAGE_BASE_RELOCATION {
+    /// RVA of the page the fixups in this block apply to
+    pub virtual_address: LittleEndian<u32>,
+    /// Size of the block in bytes, including this header and the 16-bit entries that follow it
+    pub size_of_block: LittleEndian<u32>,
+}
+
+impl IMAGE_BASE_RELOCATION {
+    /// Returns the virtual address.
+    pub fn virtual_address(&self) -> u32 {
+        self.virtual_address.into()
+    }
+
+    /// Sets the virtual address.
+    pub fn set_virtual_address(&mut self, value: u32) {
+        self.virtual_address = value.into();
+    }
+
+    /// Returns the size of the block.
+    pub fn size_of_block(&self) -> u32 {
+        self.size_of_block.into()
+    }
+
+    /// Sets the size of the block.
+    pub fn set_size_of_block(&mut self, value: u32) {
+        self.size_of_block = value.into();
+    }
+}
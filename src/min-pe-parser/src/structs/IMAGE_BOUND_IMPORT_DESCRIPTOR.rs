@@ -0,0 +1,44 @@
+use simple_endian::LittleEndian;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IMAGE_BOUND_IMPORT_DESCRIPTOR {
+    /// Time and date stamp of the DLL the imports were bound to
+    pub time_date_stamp: LittleEndian<u32>,
+    /// Offset of the DLL name, from the start of the bound import directory
+    pub offset_module_name: LittleEndian<u16>,
+    /// Number of IMAGE_BOUND_FORWARDER_REF structs following this one
+    pub number_of_module_forwarder_refs: LittleEndian<u16>,
+}
+
+impl IMAGE_BOUND_IMPORT_DESCRIPTOR {
+    /// Returns the time and date stamp.
+    pub fn time_date_stamp(&self) -> u32 {
+        self.time_date_stamp.into()
+    }
+
+    /// Sets the time and date stamp.
+    pub fn set_time_date_stamp(&mut self, value: u32) {
+        self.time_date_stamp = value.into();
+    }
+
+    /// Returns the offset of the module name.
+    pub fn offset_module_name(&self) -> u16 {
+        self.offset_module_name.into()
+    }
+
+    /// Sets the offset of the module name.
+    pub fn set_offset_module_name(&mut self, value: u16) {
+        self.offset_module_name = value.into();
+    }
+
+    /// Returns the number of module forwarder refs.
+    pub fn number_of_module_forwarder_refs(&self) -> u16 {
+        self.number_of_module_forwarder_refs.into()
+    }
+
+    /// Sets the number of module forwarder refs.
+    pub fn set_number_of_module_forwarder_refs(&mut self, value: u16) {
+        self.number_of_module_forwarder_refs = value.into();
+    }
+}
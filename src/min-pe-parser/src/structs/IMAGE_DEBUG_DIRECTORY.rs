@@ -0,0 +1,105 @@
+use crate::prelude::DebugType;
+use simple_endian::LittleEndian;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IMAGE_DEBUG_DIRECTORY {
+    /// Reserved, must be 0
+    pub characteristics: LittleEndian<u32>,
+    /// Time and date the debug data was created
+    pub time_date_stamp: LittleEndian<u32>,
+    /// Major version number of the debug data format
+    pub major_version: LittleEndian<u16>,
+    /// Minor version number of the debug data format
+    pub minor_version: LittleEndian<u16>,
+    /// Format of the debug data, see [`DebugType`]
+    pub ty: LittleEndian<u32>,
+    /// Size of the debug data, not including the debug directory itself
+    pub size_of_data: LittleEndian<u32>,
+    /// RVA of the debug data when loaded, or 0 if it is not mapped
+    pub address_of_raw_data: LittleEndian<u32>,
+    /// File offset of the debug data
+    pub pointer_to_raw_data: LittleEndian<u32>,
+}
+
+impl IMAGE_DEBUG_DIRECTORY {
+    /// Returns the characteristics.
+    pub fn characteristics(&self) -> u32 {
+        self.characteristics.into()
+    }
+
+    /// Sets the characteristics.
+    pub fn set_characteristics(&mut self, value: u32) {
+        self.characteristics = value.into();
+    }
+
+    /// Returns the time date stamp.
+    pub fn time_date_stamp(&self) -> u32 {
+        self.time_date_stamp.into()
+    }
+
+    /// Sets the time date stamp.
+    pub fn set_time_date_stamp(&mut self, value: u32) {
+        self.time_date_stamp = value.into();
+    }
+
+    /// Returns the major version.
+    pub fn major_version(&self) -> u16 {
+        self.major_version.into()
+    }
+
+    /// Sets the major version.
+    pub fn set_major_version(&mut self, value: u16) {
+        self.major_version = value.into();
+    }
+
+    /// Returns the minor version.
+    pub fn minor_version(&self) -> u16 {
+        self.minor_version.into()
+    }
+
+    /// Sets the minor version.
+    pub fn set_minor_version(&mut self, value: u16) {
+        self.minor_version = value.into();
+    }
+
+    /// Returns the type of the debug data.
+    pub fn ty(&self) -> DebugType {
+        DebugType::new(self.ty.into())
+    }
+
+    /// Sets the type of the debug data.
+    pub fn set_ty(&mut self, value: DebugType) {
+        self.ty = value.value().into();
+    }
+
+    /// Returns the size of data.
+    pub fn size_of_data(&self) -> u32 {
+        self.size_of_data.into()
+    }
+
+    /// Sets the size of data.
+    pub fn set_size_of_data(&mut self, value: u32) {
+        self.size_of_data = value.into();
+    }
+
+    /// Returns the address of raw data.
+    pub fn address_of_raw_data(&self) -> u32 {
+        self.address_of_raw_data.into()
+    }
+
+    /// Sets the address of raw data.
+    pub fn set_address_of_raw_data(&mut self, value: u32) {
+        self.address_of_raw_data = value.into();
+    }
+
+    /// Returns the pointer to raw data.
+    pub fn pointer_to_raw_data(&self) -> u32 {
+        self.pointer_to_raw_data.into()
+    }
+
+    /// Sets the pointer to raw data.
+    pub fn set_pointer_to_raw_data(&mut self, value: u32) {
+        self.pointer_to_raw_data = value.into();
+    }
+}
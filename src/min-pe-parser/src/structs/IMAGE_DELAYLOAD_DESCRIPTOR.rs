@@ -0,0 +1,112 @@
+use simple_endian::LittleEndian;
+
+/// Set in [`IMAGE_DELAYLOAD_DESCRIPTOR::attributes`] if the descriptor holds RVAs rather than VAs.
+pub const DELAYLOAD_RVA_BASED: u32 = 0x1;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IMAGE_DELAYLOAD_DESCRIPTOR {
+    /// Attributes (grAttrs)
+    pub attributes: LittleEndian<u32>,
+    /// RVA to the name of the DLL
+    pub dll_name_rva: LittleEndian<u32>,
+    /// RVA to the module handle
+    pub module_handle_rva: LittleEndian<u32>,
+    /// RVA to the delay load import address table
+    pub import_address_table_rva: LittleEndian<u32>,
+    /// RVA to the delay load import name table
+    pub import_name_table_rva: LittleEndian<u32>,
+    /// RVA to the optional bound import address table
+    pub bound_import_address_table_rva: LittleEndian<u32>,
+    /// RVA to the optional copy of the original import address table
+    pub unload_information_table_rva: LittleEndian<u32>,
+    /// Time and date stamp of the DLL this was bound to, or 0 if not bound
+    pub time_date_stamp: LittleEndian<u32>,
+}
+
+impl IMAGE_DELAYLOAD_DESCRIPTOR {
+    /// Returns the attributes.
+    pub fn attributes(&self) -> u32 {
+        self.attributes.into()
+    }
+
+    /// Sets the attributes.
+    pub fn set_attributes(&mut self, value: u32) {
+        self.attributes = value.into();
+    }
+
+    /// Returns the DLL name RVA.
+    pub fn dll_name_rva(&self) -> u32 {
+        self.dll_name_rva.into()
+    }
+
+    /// Sets the DLL name RVA.
+    pub fn set_dll_name_rva(&mut self, value: u32) {
+        self.dll_name_rva = value.into();
+    }
+
+    /// Returns the module handle RVA.
+    pub fn module_handle_rva(&self) -> u32 {
+        self.module_handle_rva.into()
+    }
+
+    /// Sets the module handle RVA.
+    pub fn set_module_handle_rva(&mut self, value: u32) {
+        self.module_handle_rva = value.into();
+    }
+
+    /// Returns the import address table RVA.
+    pub fn import_address_table_rva(&self) -> u32 {
+        self.import_address_table_rva.into()
+    }
+
+    /// Sets the import address table RVA.
+    pub fn set_import_address_table_rva(&mut self, value: u32) {
+        self.import_address_table_rva = value.into();
+    }
+
+    /// Returns the import name table RVA.
+    pub fn import_name_table_rva(&self) -> u32 {
+        self.import_name_table_rva.into()
+    }
+
+    /// Sets the import name table RVA.
+    pub fn set_import_name_table_rva(&mut self, value: u32) {
+        self.import_name_table_rva = value.into();
+    }
+
+    /// Returns the bound import address table RVA.
+    pub fn bound_import_address_table_rva(&self) -> u32 {
+        self.bound_import_address_table_rva.into()
+    }
+
+    /// Sets the bound import address table RVA.
+    pub fn set_bound_import_address_table_rva(&mut self, value: u32) {
+        self.bound_import_address_table_rva = value.into();
+    }
+
+    /// Returns the unload information table RVA.
+    pub fn unload_information_table_rva(&self) -> u32 {
+        self.unload_information_table_rva.into()
+    }
+
+    /// Sets the unload information table RVA.
+    pub fn set_unload_information_table_rva(&mut self, value: u32) {
+        self.unload_information_table_rva = value.into();
+    }
+
+    /// Returns the time and date stamp.
+    pub fn time_date_stamp(&self) -> u32 {
+        self.time_date_stamp.into()
+    }
+
+    /// Sets the time and date stamp.
+    pub fn set_time_date_stamp(&mut self, value: u32) {
+        self.time_date_stamp = value.into();
+    }
+
+    /// Checks if the descriptor holds RVAs, rather than the VAs of the legacy (Visual C++ 6.0) layout.
+    pub fn is_rva_based(&self) -> bool {
+        self.attributes() & DELAYLOAD_RVA_BASED != 0
+    }
+}
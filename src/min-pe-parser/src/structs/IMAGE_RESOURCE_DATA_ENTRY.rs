@@ -0,0 +1,56 @@
+use simple_endian::LittleEndian;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IMAGE_RESOURCE_DATA_ENTRY {
+    /// RVA of the resource data
+    pub offset_to_data: LittleEndian<u32>,
+    /// Size of the resource data in bytes
+    pub size: LittleEndian<u32>,
+    /// Code page used to decode code point values within the resource data
+    pub code_page: LittleEndian<u32>,
+    /// Reserved, must be 0
+    pub reserved: LittleEndian<u32>,
+}
+
+impl IMAGE_RESOURCE_DATA_ENTRY {
+    /// Returns the offset to data.
+    pub fn offset_to_data(&self) -> u32 {
+        self.offset_to_data.into()
+    }
+
+    /// Sets the offset to data.
+    pub fn set_offset_to_data(&mut self, value: u32) {
+        self.offset_to_data = value.into();
+    }
+
+    /// Returns the size.
+    pub fn size(&self) -> u32 {
+        self.size.into()
+    }
+
+    /// Sets the size.
+    pub fn set_size(&mut self, value: u32) {
+        self.size = value.into();
+    }
+
+    /// Returns the code page.
+    pub fn code_page(&self) -> u32 {
+        self.code_page.into()
+    }
+
+    /// Sets the code page.
+    pub fn set_code_page(&mut self, value: u32) {
+        self.code_page = value.into();
+    }
+
+    /// Returns the reserved field.
+    pub fn reserved(&self) -> u32 {
+        self.reserved.into()
+    }
+
+    /// Sets the reserved field.
+    pub fn set_reserved(&mut self, value: u32) {
+        self.reserved = value.into();
+    }
+}
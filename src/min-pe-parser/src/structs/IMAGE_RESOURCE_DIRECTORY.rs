@@ -0,0 +1,85 @@
+use simple_endian::LittleEndian;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IMAGE_RESOURCE_DIRECTORY {
+    /// Characteristics, reserved
+    pub characteristics: LittleEndian<u32>,
+    /// Time and date stamp of when the resource data was created
+    pub time_date_stamp: LittleEndian<u32>,
+    /// Major version number
+    pub major_version: LittleEndian<u16>,
+    /// Minor version number
+    pub minor_version: LittleEndian<u16>,
+    /// Number of entries named by a string, which come first
+    pub number_of_named_entries: LittleEndian<u16>,
+    /// Number of entries named by an ID, which follow the named entries
+    pub number_of_id_entries: LittleEndian<u16>,
+}
+
+impl IMAGE_RESOURCE_DIRECTORY {
+    /// Returns the characteristics.
+    pub fn characteristics(&self) -> u32 {
+        self.characteristics.into()
+    }
+
+    /// Sets the characteristics.
+    pub fn set_characteristics(&mut self, value: u32) {
+        self.characteristics = value.into();
+    }
+
+    /// Returns the time and date stamp.
+    pub fn time_date_stamp(&self) -> u32 {
+        self.time_date_stamp.into()
+    }
+
+    /// Sets the time and date stamp.
+    pub fn set_time_date_stamp(&mut self, value: u32) {
+        self.time_date_stamp = value.into();
+    }
+
+    /// Returns the major version.
+    pub fn major_version(&self) -> u16 {
+        self.major_version.into()
+    }
+
+    /// Sets the major version.
+    pub fn set_major_version(&mut self, value: u16) {
+        self.major_version = value.into();
+    }
+
+    /// Returns the minor version.
+    pub fn minor_version(&self) -> u16 {
+        self.minor_version.into()
+    }
+
+    /// Sets the minor version.
+    pub fn set_minor_version(&mut self, value: u16) {
+        self.minor_version = value.into();
+    }
+
+    /// Returns the number of named entries.
+    pub fn number_of_named_entries(&self) -> u16 {
+        self.number_of_named_entries.into()
+    }
+
+    /// Sets the number of named entries.
+    pub fn set_number_of_named_entries(&mut self, value: u16) {
+        self.number_of_named_entries = value.into();
+    }
+
+    /// Returns the number of ID entries.
+    pub fn number_of_id_entries(&self) -> u16 {
+        self.number_of_id_entries.into()
+    }
+
+    /// Sets the number of ID entries.
+    pub fn set_number_of_id_entries(&mut self, value: u16) {
+        self.number_of_id_entries = value.into();
+    }
+
+    /// Returns the total number of entries which follow the directory.
+    pub fn number_of_entries(&self) -> usize {
+        self.number_of_named_entries() as usize + self.number_of_id_entries() as usize
+    }
+}
@@ -0,0 +1,67 @@
+use simple_endian::LittleEndian;
+
+/// Set in [`IMAGE_RESOURCE_DIRECTORY_ENTRY::name`] if the entry is named by a string rather than an ID.
+pub const IMAGE_RESOURCE_NAME_IS_STRING: u32 = 0x8000_0000;
+
+/// Set in [`IMAGE_RESOURCE_DIRECTORY_ENTRY::offset_to_data`] if the entry points to another directory
+/// rather than an [`IMAGE_RESOURCE_DATA_ENTRY`](crate::structs::IMAGE_RESOURCE_DATA_ENTRY::IMAGE_RESOURCE_DATA_ENTRY).
+pub const IMAGE_RESOURCE_DATA_IS_DIRECTORY: u32 = 0x8000_0000;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IMAGE_RESOURCE_DIRECTORY_ENTRY {
+    /// ID of the entry, or the offset of its name (with the high bit set),
+    /// from the start of the resource directory
+    pub name: LittleEndian<u32>,
+    /// Offset of the data entry, or of the subdirectory (with the high bit set),
+    /// from the start of the resource directory
+    pub offset_to_data: LittleEndian<u32>,
+}
+
+impl IMAGE_RESOURCE_DIRECTORY_ENTRY {
+    /// Returns the name.
+    pub fn name(&self) -> u32 {
+        self.name.into()
+    }
+
+    /// Sets the name.
+    pub fn set_name(&mut self, value: u32) {
+        self.name = value.into();
+    }
+
+    /// Returns the offset to data.
+    pub fn offset_to_data(&self) -> u32 {
+        self.offset_to_data.into()
+    }
+
+    /// Sets the offset to data.
+    pub fn set_offset_to_data(&mut self, value: u32) {
+        self.offset_to_data = value.into();
+    }
+
+    /// Checks if the entry is named by a string, rather than an ID.
+    pub fn is_named(&self) -> bool {
+        self.name() & IMAGE_RESOURCE_NAME_IS_STRING != 0
+    }
+
+    /// Returns the ID of the entry. Only meaningful if it is not [named](Self::is_named).
+    pub fn id(&self) -> u16 {
+        self.name() as u16
+    }
+
+    /// Returns the offset of the name string, from the start of the resource directory.
+    /// Only meaningful if the entry is [named](Self::is_named).
+    pub fn name_offset(&self) -> u32 {
+        self.name() & !IMAGE_RESOURCE_NAME_IS_STRING
+    }
+
+    /// Checks if the entry points to another directory, rather than a data entry.
+    pub fn is_directory(&self) -> bool {
+        self.offset_to_data() & IMAGE_RESOURCE_DATA_IS_DIRECTORY != 0
+    }
+
+    /// Returns the offset of the data entry or subdirectory, from the start of the resource directory.
+    pub fn offset(&self) -> u32 {
+        self.offset_to_data() & !IMAGE_RESOURCE_DATA_IS_DIRECTORY
+    }
+}
@@ -1,5 +1,9 @@
+use crate::prelude::*;
 use simple_endian::LittleEndian;
 
+/// Set in a thunk if the function is imported by ordinal.
+pub const IMAGE_ORDINAL_FLAG64: u64 = 0x8000_0000_0000_0000;
+
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub union IMAGE_THUNK_DATA64 {
@@ -12,3 +16,70 @@ pub union IMAGE_THUNK_DATA64 {
     /// Address of data
     address_of_data: LittleEndian<u64>,
 }
+
+impl IMAGE_THUNK_DATA64 {
+    /// Creates a new thunk from its raw value.
+    pub fn new(value: u64) -> Self {
+        IMAGE_THUNK_DATA64 { function: value.into() }
+    }
+
+    /// Returns the raw value of the thunk. A null thunk terminates the table.
+    pub fn value(&self) -> u64 {
+        // SAFETY: All fields are the same plain integer.
+        unsafe { self.function }.into()
+    }
+
+    /// Returns the forwarder string.
+    pub fn forwarder_string(&self) -> u64 {
+        self.value()
+    }
+
+    /// Sets the forwarder string.
+    pub fn set_forwarder_string(&mut self, value: u64) {
+        self.forwarder_string = value.into();
+    }
+
+    /// Returns the function address.
+    pub fn function(&self) -> u64 {
+        self.value()
+    }
+
+    /// Sets the function address.
+    pub fn set_function(&mut self, value: u64) {
+        self.function = value.into();
+    }
+
+    /// Returns the ordinal, including [`IMAGE_ORDINAL_FLAG64`].
+    pub fn ordinal(&self) -> u64 {
+        self.value()
+    }
+
+    /// Sets the ordinal, including [`IMAGE_ORDINAL_FLAG64`].
+    pub fn set_ordinal(&mut self, value: u64) {
+        self.ordinal = value.into();
+    }
+
+    /// Returns the address of data.
+    pub fn address_of_data(&self) -> u64 {
+        self.value()
+    }
+
+    /// Sets the address of data.
+    pub fn set_address_of_data(&mut self, value: u64) {
+        self.address_of_data = value.into();
+    }
+
+    /// Checks if the function is imported by ordinal.
+    pub fn is_ordinal(&self) -> bool {
+        self.value() & IMAGE_ORDINAL_FLAG64 != 0
+    }
+
+    /// Decodes the thunk.
+    ///
+    /// # Arguments
+    /// - `is_bound` - Whether the thunk is in a bound (or resolved) import address table,
+    ///   in which case it holds the address of the function.
+    pub fn decode(&self, is_bound: bool) -> Thunk {
+        Thunk::decode(self.value(), IMAGE_ORDINAL_FLAG64, is_bound)
+    }
+}
@@ -0,0 +1,182 @@
+use simple_endian::LittleEndian;
+
+/// Value of [`VS_FIXEDFILEINFO::signature`].
+pub const VS_FFI_SIGNATURE: u32 = 0xFEEF_04BD;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VS_FIXEDFILEINFO {
+    /// Signature, always 0xFEEF04BD
+    pub signature: LittleEndian<u32>,
+    /// Binary version of the structure
+    pub struc_version: LittleEndian<u32>,
+    /// Most significant 32 bits of the file version
+    pub file_version_ms: LittleEndian<u32>,
+    /// Least significant 32 bits of the file version
+    pub file_version_ls: LittleEndian<u32>,
+    /// Most significant 32 bits of the product version
+    pub product_version_ms: LittleEndian<u32>,
+    /// Least significant 32 bits of the product version
+    pub product_version_ls: LittleEndian<u32>,
+    /// Bits of file_flags which are valid
+    pub file_flags_mask: LittleEndian<u32>,
+    /// Attributes of the file, e.g. debug or prerelease
+    pub file_flags: LittleEndian<u32>,
+    /// Operating system the file was designed for
+    pub file_os: LittleEndian<u32>,
+    /// General type of the file, e.g. application or DLL
+    pub file_type: LittleEndian<u32>,
+    /// Function of the file, e.g. the type of driver or font
+    pub file_subtype: LittleEndian<u32>,
+    /// Most significant 32 bits of the file's creation date
+    pub file_date_ms: LittleEndian<u32>,
+    /// Least significant 32 bits of the file's creation date
+    pub file_date_ls: LittleEndian<u32>,
+}
+
+impl VS_FIXEDFILEINFO {
+    /// Returns the signature.
+    pub fn signature(&self) -> u32 {
+        self.signature.into()
+    }
+
+    /// Sets the signature.
+    pub fn set_signature(&mut self, value: u32) {
+        self.signature = value.into();
+    }
+
+    /// Returns the structure version.
+    pub fn struc_version(&self) -> u32 {
+        self.struc_version.into()
+    }
+
+    /// Sets the structure version.
+    pub fn set_struc_version(&mut self, value: u32) {
+        self.struc_version = value.into();
+    }
+
+    /// Returns the most significant file version.
+    pub fn file_version_ms(&self) -> u32 {
+        self.file_version_ms.into()
+    }
+
+    /// Sets the most significant file version.
+    pub fn set_file_version_ms(&mut self, value: u32) {
+        self.file_version_ms = value.into();
+    }
+
+    /// Returns the least significant file version.
+    pub fn file_version_ls(&self) -> u32 {
+        self.file_version_ls.into()
+    }
+
+    /// Sets the least significant file version.
+    pub fn set_file_version_ls(&mut self, value: u32) {
+        self.file_version_ls = value.into();
+    }
+
+    /// Returns the most significant product version.
+    pub fn product_version_ms(&self) -> u32 {
+        self.product_version_ms.into()
+    }
+
+    /// Sets the most significant product version.
+    pub fn set_product_version_ms(&mut self, value: u32) {
+        self.product_version_ms = value.into();
+    }
+
+    /// Returns the least significant product version.
+    pub fn product_version_ls(&self) -> u32 {
+        self.product_version_ls.into()
+    }
+
+    /// Sets the least significant product version.
+    pub fn set_product_version_ls(&mut self, value: u32) {
+        self.product_version_ls = value.into();
+    }
+
+    /// Returns the file flags mask.
+    pub fn file_flags_mask(&self) -> u32 {
+        self.file_flags_mask.into()
+    }
+
+    /// Sets the file flags mask.
+    pub fn set_file_flags_mask(&mut self, value: u32) {
+        self.file_flags_mask = value.into();
+    }
+
+    /// Returns the file flags.
+    pub fn file_flags(&self) -> u32 {
+        self.file_flags.into()
+    }
+
+    /// Sets the file flags.
+    pub fn set_file_flags(&mut self, value: u32) {
+        self.file_flags = value.into();
+    }
+
+    /// Returns the file OS.
+    pub fn file_os(&self) -> u32 {
+        self.file_os.into()
+    }
+
+    /// Sets the file OS.
+    pub fn set_file_os(&mut self, value: u32) {
+        self.file_os = value.into();
+    }
+
+    /// Returns the file type.
+    pub fn file_type(&self) -> u32 {
+        self.file_type.into()
+    }
+
+    /// Sets the file type.
+    pub fn set_file_type(&mut self, value: u32) {
+        self.file_type = value.into();
+    }
+
+    /// Returns the file subtype.
+    pub fn file_subtype(&self) -> u32 {
+        self.file_subtype.into()
+    }
+
+    /// Sets the file subtype.
+    pub fn set_file_subtype(&mut self, value: u32) {
+        self.file_subtype = value.into();
+    }
+
+    /// Returns the most significant file date.
+    pub fn file_date_ms(&self) -> u32 {
+        self.file_date_ms.into()
+    }
+
+    /// Sets the most significant file date.
+    pub fn set_file_date_ms(&mut self, value: u32) {
+        self.file_date_ms = value.into();
+    }
+
+    /// Returns the least significant file date.
+    pub fn file_date_ls(&self) -> u32 {
+        self.file_date_ls.into()
+    }
+
+    /// Sets the least significant file date.
+    pub fn set_file_date_ls(&mut self, value: u32) {
+        self.file_date_ls = value.into();
+    }
+
+    /// Returns the file version, as `[major, minor, build, revision]`.
+    pub fn file_version(&self) -> [u16; 4] {
+        split_version(self.file_version_ms(), self.file_version_ls())
+    }
+
+    /// Returns the product version, as `[major, minor, build, revision]`.
+    pub fn product_version(&self) -> [u16; 4] {
+        split_version(self.product_version_ms(), self.product_version_ls())
+    }
+}
+
+/// Splits a version stored as two 32-bit halves into its four 16-bit parts.
+fn split_version(ms: u32, ls: u32) -> [u16; 4] {
+    [(ms >> 16) as u16, ms as u16, (ls >> 16) as u16, ls as u16]
+}
@@ -0,0 +1,52 @@
+/// The format of a debug directory entry's data (`IMAGE_DEBUG_TYPE_*`).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DebugType {
+    value: u32,
+}
+
+impl DebugType {
+    pub const UNKNOWN: DebugType = DebugType { value: 0 };
+    pub const COFF: DebugType = DebugType { value: 1 };
+    pub const CODEVIEW: DebugType = DebugType { value: 2 };
+    pub const FPO: DebugType = DebugType { value: 3 };
+    pub const MISC: DebugType = DebugType { value: 4 };
+    pub const EXCEPTION: DebugType = DebugType { value: 5 };
+    pub const FIXUP: DebugType = DebugType { value: 6 };
+    pub const OMAP_TO_SRC: DebugType = DebugType { value: 7 };
+    pub const OMAP_FROM_SRC: DebugType = DebugType { value: 8 };
+    pub const BORLAND: DebugType = DebugType { value: 9 };
+    pub const RESERVED10: DebugType = DebugType { value: 10 };
+    pub const CLSID: DebugType = DebugType { value: 11 };
+    pub const VC_FEATURE: DebugType = DebugType { value: 12 };
+    pub const POGO: DebugType = DebugType { value: 13 };
+    pub const ILTCG: DebugType = DebugType { value: 14 };
+    pub const MPX: DebugType = DebugType { value: 15 };
+    pub const REPRO: DebugType = DebugType { value: 16 };
+    pub const EMBEDDED_PORTABLE_PDB: DebugType = DebugType { value: 17 };
+    pub const SPGO: DebugType = DebugType { value: 18 };
+    pub const PDBCHECKSUM: DebugType = DebugType { value: 19 };
+    pub const EX_DLLCHARACTERISTICS: DebugType = DebugType { value: 20 };
+
+    /// Creates a new `DebugType` instance from a `u32`.
+    pub fn new(value: u32) -> Self {
+        DebugType { value }
+    }
+
+    /// Returns the inner `u32` value.
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+impl From<DebugType> for u32 {
+    fn from(ty: DebugType) -> Self {
+        ty.value()
+    }
+}
+
+impl From<u32> for DebugType {
+    fn from(value: u32) -> Self {
+        DebugType::new(value)
+    }
+}
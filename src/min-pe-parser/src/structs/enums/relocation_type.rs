@@ -0,0 +1,54 @@
+/// The type of a base relocation, stored in the top 4 bits of each entry
+/// following an [`IMAGE_BASE_RELOCATION`].
+///
+/// Some values have a different meaning depending on the machine type,
+/// hence the aliases below.
+///
+/// [`IMAGE_BASE_RELOCATION`]: crate::structs::IMAGE_BASE_RELOCATION::IMAGE_BASE_RELOCATION
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RelocationType {
+    value: u8,
+}
+
+impl RelocationType {
+    /// Skipped, used to pad a block to a 32-bit boundary.
+    pub const ABSOLUTE: RelocationType = RelocationType { value: 0 };
+    /// Adds the high 16 bits of the difference to the 16-bit field.
+    pub const HIGH: RelocationType = RelocationType { value: 1 };
+    /// Adds the low 16 bits of the difference to the 16-bit field.
+    pub const LOW: RelocationType = RelocationType { value: 2 };
+    /// Adds all 32 bits of the difference to the 32-bit field.
+    pub const HIGHLOW: RelocationType = RelocationType { value: 3 };
+    /// Adds the high 16 bits of the difference to the 16-bit field, with the low
+    /// 16 bits of the full 32-bit value taken from the following entry.
+    pub const HIGHADJ: RelocationType = RelocationType { value: 4 };
+    /// ARM: A `MOVW`/`MOVT` instruction pair holding a 32-bit address.
+    pub const ARM_MOV32: RelocationType = RelocationType { value: 5 };
+    /// Thumb-2: A `MOVW`/`MOVT` instruction pair holding a 32-bit address.
+    pub const THUMB_MOV32: RelocationType = RelocationType { value: 7 };
+    /// Adds the difference to the 64-bit field. Used by both x64 and ARM64.
+    pub const DIR64: RelocationType = RelocationType { value: 10 };
+
+    /// Creates a new `RelocationType` instance from a `u8`.
+    pub fn new(value: u8) -> Self {
+        RelocationType { value }
+    }
+
+    /// Returns the inner `u8` value.
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+}
+
+impl From<RelocationType> for u8 {
+    fn from(ty: RelocationType) -> Self {
+        ty.value()
+    }
+}
+
+impl From<u8> for RelocationType {
+    fn from(value: u8) -> Self {
+        RelocationType::new(value)
+    }
+}
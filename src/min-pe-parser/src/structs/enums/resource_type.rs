@@ -0,0 +1,52 @@
+/// The ID of a standard resource type (`RT_*`), used at the top level of the resource directory.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ResourceType {
+    value: u16,
+}
+
+impl ResourceType {
+    pub const CURSOR: ResourceType = ResourceType { value: 1 };
+    pub const BITMAP: ResourceType = ResourceType { value: 2 };
+    pub const ICON: ResourceType = ResourceType { value: 3 };
+    pub const MENU: ResourceType = ResourceType { value: 4 };
+    pub const DIALOG: ResourceType = ResourceType { value: 5 };
+    pub const STRING: ResourceType = ResourceType { value: 6 };
+    pub const FONTDIR: ResourceType = ResourceType { value: 7 };
+    pub const FONT: ResourceType = ResourceType { value: 8 };
+    pub const ACCELERATOR: ResourceType = ResourceType { value: 9 };
+    pub const RCDATA: ResourceType = ResourceType { value: 10 };
+    pub const MESSAGETABLE: ResourceType = ResourceType { value: 11 };
+    pub const GROUP_CURSOR: ResourceType = ResourceType { value: 12 };
+    pub const GROUP_ICON: ResourceType = ResourceType { value: 14 };
+    pub const VERSION: ResourceType = ResourceType { value: 16 };
+    pub const DLGINCLUDE: ResourceType = ResourceType { value: 17 };
+    pub const PLUGPLAY: ResourceType = ResourceType { value: 19 };
+    pub const VXD: ResourceType = ResourceType { value: 20 };
+    pub const ANICURSOR: ResourceType = ResourceType { value: 21 };
+    pub const ANIICON: ResourceType = ResourceType { value: 22 };
+    pub const HTML: ResourceType = ResourceType { value: 23 };
+    pub const MANIFEST: ResourceType = ResourceType { value: 24 };
+
+    /// Creates a new `ResourceType` instance from a `u16`.
+    pub fn new(value: u16) -> Self {
+        ResourceType { value }
+    }
+
+    /// Returns the inner `u16` value.
+    pub fn value(&self) -> u16 {
+        self.value
+    }
+}
+
+impl From<ResourceType> for u16 {
+    fn from(ty: ResourceType) -> Self {
+        ty.value()
+    }
+}
+
+impl From<u16> for ResourceType {
+    fn from(value: u16) -> Self {
+        ResourceType::new(value)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::name_or_id::NameOrId;
+
+/// The decoded contents of an [`IMAGE_THUNK_DATA32`] or [`IMAGE_THUNK_DATA64`].
+///
+/// [`IMAGE_THUNK_DATA32`]: crate::structs::IMAGE_THUNK_DATA32::IMAGE_THUNK_DATA32
+/// [`IMAGE_THUNK_DATA64`]: crate::structs::IMAGE_THUNK_DATA64::IMAGE_THUNK_DATA64
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Thunk {
+    /// The function is imported by ordinal.
+    Ordinal(u16),
+    /// The function is imported by name.
+    ByName {
+        /// RVA of the [`IMAGE_IMPORT_BY_NAME`] holding the hint and name.
+        ///
+        /// [`IMAGE_IMPORT_BY_NAME`]: crate::structs::IMAGE_IMPORT_BY_NAME::IMAGE_IMPORT_BY_NAME
+        hint_name_rva: u32,
+    },
+    /// The import has been resolved (or bound) to this address.
+    Bound(u64),
+}
+
+impl Thunk {
+    /// Decodes the raw value of a thunk, where `ordinal_flag` is the high bit of the thunk.
+    /// Thunks in a bound (or resolved) import address table hold addresses.
+    #[inline]
+    pub(crate) fn decode(value: u64, ordinal_flag: u64, is_bound: bool) -> Self {
+        if is_bound {
+            return Thunk::Bound(value);
+        }
+
+        match Self::decode_lookup(value, ordinal_flag) {
+            NameOrId::Id(ordinal) => Thunk::Ordinal(ordinal),
+            NameOrId::Name(hint_name_rva) => Thunk::ByName { hint_name_rva },
+        }
+    }
+
+    /// Decodes the raw value of a thunk in an import lookup (or name) table, which never holds an address.
+    /// Returns the ordinal, or the RVA of the [`IMAGE_IMPORT_BY_NAME`] holding the hint and name.
+    ///
+    /// [`IMAGE_IMPORT_BY_NAME`]: crate::structs::IMAGE_IMPORT_BY_NAME::IMAGE_IMPORT_BY_NAME
+    #[inline]
+    pub(crate) fn decode_lookup(value: u64, ordinal_flag: u64) -> NameOrId<u32> {
+        if value & ordinal_flag != 0 {
+            NameOrId::Id(value as u16)
+        } else {
+            NameOrId::Name(value as u32)
+        }
+    }
+}
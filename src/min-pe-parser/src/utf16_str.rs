@@ -0,0 +1,112 @@
+use core::{
+    char::{decode_utf16, REPLACEMENT_CHARACTER},
+    fmt::{Debug, Display, Formatter, Write},
+};
+
+/// A borrowed UTF-16LE string, as used by resources.
+///
+/// The string is kept as raw bytes, since strings within the PE file are not guaranteed
+/// to be aligned for `u16`. Invalid UTF-16 decodes to [`REPLACEMENT_CHARACTER`].
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub struct Utf16Str<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Utf16Str<'a> {
+    /// Creates a string from UTF-16LE bytes. A trailing odd byte is ignored.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Utf16Str {
+            bytes: &bytes[..bytes.len() & !1],
+        }
+    }
+
+    /// Creates a string from the UTF-16LE bytes up to (not including) the first null terminator.
+    ///
+    /// # Returns
+    /// The string, and the bytes after its terminator. [`None`] if there is no terminator.
+    pub fn from_nul_terminated(bytes: &'a [u8]) -> Option<(Self, &'a [u8])> {
+        let len = bytes.chunks_exact(2).position(|x| x == [0, 0])? * 2;
+        Some((Utf16Str::new(&bytes[..len]), &bytes[len + 2..]))
+    }
+
+    /// Returns the underlying UTF-16LE bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the length of the string, in UTF-16 code units.
+    pub fn len(&self) -> usize {
+        self.bytes.len() / 2
+    }
+
+    /// Checks if the string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns an iterator over the UTF-16 code units of the string.
+    pub fn units(&self) -> impl Iterator<Item = u16> + 'a {
+        self.bytes.chunks_exact(2).map(|x| u16::from_le_bytes([x[0], x[1]]))
+    }
+
+    /// Returns an iterator over the characters of the string.
+    pub fn chars(&self) -> impl Iterator<Item = char> + 'a {
+        decode_utf16(self.units()).map(|x| x.unwrap_or(REPLACEMENT_CHARACTER))
+    }
+
+    /// Checks if the string matches `other`, ignoring ASCII case, as the loader
+    /// does when looking up resources by name.
+    pub fn eq_ignore_ascii_case(&self, other: &str) -> bool {
+        self.chars()
+            .map(|x| x.to_ascii_lowercase())
+            .eq(other.chars().map(|x| x.to_ascii_lowercase()))
+    }
+}
+
+impl PartialEq<str> for Utf16Str<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+
+impl PartialEq<&str> for Utf16Str<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl Display for Utf16Str<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.chars().try_for_each(|x| f.write_char(x))
+    }
+}
+
+impl Debug for Utf16Str<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_char('"')?;
+        self.chars().try_for_each(|x| Display::fmt(&x.escape_debug(), f))?;
+        f.write_char('"')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_utf16_str() {
+        let (string, rest) = Utf16Str::from_nul_terminated(b"I\0c\0o\0n\0\0\0!\0").unwrap();
+        assert_eq!(string.len(), 4);
+        assert_eq!(string, "Icon");
+        assert!(string.eq_ignore_ascii_case("ICON"));
+        assert!(!string.eq_ignore_ascii_case("ICONS"));
+        assert_eq!(rest, b"!\0");
+        assert_eq!(string.to_string(), "Icon");
+        assert_eq!(alloc::format!("{:?}", string), "\"Icon\"");
+
+        // Lone surrogate.
+        assert_eq!(Utf16Str::new(b"\x00\xD8a\0\0").to_string(), "\u{FFFD}a");
+        assert_eq!(Utf16Str::from_nul_terminated(b"a\0b"), None);
+    }
+}
@@ -0,0 +1,75 @@
+use alloc::{vec, vec::Vec};
+use core::{
+    ops::{Deref, DerefMut},
+    slice,
+};
+
+/// A zero initialized byte buffer, aligned to 8 bytes so it can always be parsed with
+/// [`PeView`](crate::pe_view::PeView). Dereferences to `[u8]`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AlignedBuffer {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl AlignedBuffer {
+    /// Creates a new buffer of `len` zero bytes.
+    pub fn new(len: usize) -> Self {
+        AlignedBuffer {
+            words: vec![0; len.div_ceil(8)],
+            len,
+        }
+    }
+
+    /// Resizes the buffer to `len` bytes. New bytes are zero.
+    pub fn resize(&mut self, len: usize) {
+        // Bytes past the end are kept zero, so they do not reappear when growing again.
+        if len < self.len {
+            self[len..].fill(0);
+        }
+
+        self.words.resize(len.div_ceil(8), 0);
+        self.len = len;
+    }
+}
+
+impl From<&[u8]> for AlignedBuffer {
+    fn from(data: &[u8]) -> Self {
+        let mut buffer = AlignedBuffer::new(data.len());
+        buffer.copy_from_slice(data);
+        buffer
+    }
+}
+
+impl Deref for AlignedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: `words` holds at least `len` bytes.
+        unsafe { slice::from_raw_parts(self.words.as_ptr() as *const u8, self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: `words` holds at least `len` bytes.
+        unsafe { slice::from_raw_parts_mut(self.words.as_mut_ptr() as *mut u8, self.len) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aligned_buffer() {
+        let mut buffer = AlignedBuffer::from(&b"0123456789"[..]);
+        assert_eq!(buffer.len(), 10);
+        assert_eq!(buffer.as_ptr() as usize % 8, 0);
+        assert_eq!(&buffer[..], b"0123456789");
+
+        buffer.resize(3);
+        buffer.resize(6);
+        assert_eq!(&buffer[..], b"012\0\0\0");
+    }
+}
@@ -0,0 +1,279 @@
+use crate::{error::PeError, pe_view::PeView, prelude::*, utils::get_relocations::Relocation};
+use alloc::vec::Vec;
+use core::mem::offset_of;
+
+/// Rebases a mapped image from `old_base` to `new_base`, by applying every fixup in the
+/// base relocation table and updating the image base in the optional header.
+///
+/// # Arguments
+/// - `image` - The PE file, in the mapped (image) layout. Must be aligned to 4 bytes.
+/// - `old_base` - The address the image is currently relocated for, usually its preferred image base.
+/// - `new_base` - The address the image should be relocated for.
+///
+/// # Remarks
+/// Relocation types 5 and 7 are treated as the ARM and Thumb-2 `MOVW`/`MOVT` pairs;
+/// their MIPS meanings are not supported.
+///
+/// All fixups are validated before any are applied, so the image is left untouched on error.
+///
+/// # Returns
+/// [`PeError::MissingDirectory`] if the image needs relocating but has no base relocation table,
+/// [`PeError::UnsupportedRelocation`] if it contains a fixup which cannot be applied,
+/// [`PeError::ImageBaseOutOfRange`] if a base is above 4 GiB for PE32,
+/// or another [`PeError`] if the image is malformed.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub fn apply_relocations(image: &mut [u8], old_base: u64, new_base: u64) -> Result<(), PeError> {
+    let view = PeView::new(image, true)?;
+    let optional_header = view.optional_header_offset();
+    let is_pe64 = view.is_pe64();
+    if !is_pe64 {
+        if let Some(base) = [old_base, new_base].into_iter().find(|&x| x > u32::MAX as u64) {
+            return Err(PeError::ImageBaseOutOfRange(base));
+        }
+    }
+
+    let delta = new_base.wrapping_sub(old_base);
+    let relocations: Vec<Relocation> = match delta {
+        0 => Vec::new(),
+        _ => {
+            view.data_directory_offset(DataDirectoryType::BaseRelocationTable)?;
+            view.relocations()?.collect::<Result<_, _>>()?
+        }
+    };
+
+    for relocation in relocations.iter() {
+        let size = fixup_size(relocation.ty)?;
+        if (relocation.rva as usize).saturating_add(size) > image.len() {
+            return Err(PeError::Truncated);
+        }
+    }
+
+    for relocation in relocations.iter() {
+        let rva = relocation.rva as usize;
+        match relocation.ty {
+            RelocationType::HIGH => patch(image, rva, |x| {
+                u16::from_le_bytes(x).wrapping_add((delta >> 16) as u16).to_le_bytes()
+            }),
+            RelocationType::LOW => patch(image, rva, |x| {
+                u16::from_le_bytes(x).wrapping_add(delta as u16).to_le_bytes()
+            }),
+            RelocationType::HIGHLOW => patch(image, rva, |x| {
+                u32::from_le_bytes(x).wrapping_add(delta as u32).to_le_bytes()
+            }),
+            RelocationType::HIGHADJ => patch(image, rva, |x| {
+                // The low half is signed, and the result is rounded to the nearest high half.
+                let value = (u32::from(u16::from_le_bytes(x)) << 16)
+                    .wrapping_add(relocation.adjust as i16 as u32)
+                    .wrapping_add(delta as u32)
+                    .wrapping_add(0x8000);
+                ((value >> 16) as u16).to_le_bytes()
+            }),
+            RelocationType::DIR64 => patch(image, rva, |x| u64::from_le_bytes(x).wrapping_add(delta).to_le_bytes()),
+            RelocationType::ARM_MOV32 => patch_mov32(image, rva, delta as u32, arm_imm16, set_arm_imm16),
+            RelocationType::THUMB_MOV32 => patch_mov32(image, rva, delta as u32, thumb_imm16, set_thumb_imm16),
+            _ => {}
+        }
+    }
+
+    if is_pe64 {
+        let offset = optional_header + offset_of!(IMAGE_OPTIONAL_HEADER64, image_base);
+        patch(image, offset, |_: [u8; 8]| new_base.to_le_bytes());
+    } else {
+        let offset = optional_header + offset_of!(IMAGE_OPTIONAL_HEADER32, image_base);
+        patch(image, offset, |_: [u8; 4]| (new_base as u32).to_le_bytes());
+    }
+
+    Ok(())
+}
+
+/// Returns the number of bytes modified by a fixup of the given type.
+fn fixup_size(ty: RelocationType) -> Result<usize, PeError> {
+    match ty {
+        RelocationType::ABSOLUTE => Ok(0),
+        RelocationType::HIGH | RelocationType::LOW | RelocationType::HIGHADJ => Ok(2),
+        RelocationType::HIGHLOW => Ok(4),
+        RelocationType::DIR64 | RelocationType::ARM_MOV32 | RelocationType::THUMB_MOV32 => Ok(8),
+        _ => Err(PeError::UnsupportedRelocation(ty)),
+    }
+}
+
+/// Replaces the `N` bytes at `offset` with the result of `f`.
+fn patch<const N: usize>(image: &mut [u8], offset: usize, f: impl FnOnce([u8; N]) -> [u8; N]) {
+    let bytes = &mut image[offset..offset + N];
+    let value = f(bytes.try_into().unwrap());
+    bytes.copy_from_slice(&value);
+}
+
+/// Adds `delta` to the 32-bit value split across a `MOVW` (low half) and `MOVT` (high half) pair.
+fn patch_mov32(image: &mut [u8], offset: usize, delta: u32, imm16: fn(u32) -> u16, set_imm16: fn(u32, u16) -> u32) {
+    patch(image, offset, |x: [u8; 8]| {
+        let movw = u32::from_le_bytes([x[0], x[1], x[2], x[3]]);
+        let movt = u32::from_le_bytes([x[4], x[5], x[6], x[7]]);
+        let value = (u32::from(imm16(movt)) << 16 | u32::from(imm16(movw))).wrapping_add(delta);
+
+        let mut result = [0u8; 8];
+        result[..4].copy_from_slice(&set_imm16(movw, value as u16).to_le_bytes());
+        result[4..].copy_from_slice(&set_imm16(movt, (value >> 16) as u16).to_le_bytes());
+        result
+    });
+}
+
+/// Returns the immediate of an ARM `MOVW`/`MOVT`, encoded as `imm4:imm12` in bits 19:16 and 11:0.
+fn arm_imm16(instruction: u32) -> u16 {
+    ((instruction >> 4) & 0xF000 | instruction & 0xFFF) as u16
+}
+
+/// Sets the immediate of an ARM `MOVW`/`MOVT`.
+fn set_arm_imm16(instruction: u32, imm16: u16) -> u32 {
+    let imm16 = u32::from(imm16);
+    instruction & 0xFFF0_F000 | (imm16 & 0xF000) << 4 | imm16 & 0xFFF
+}
+
+/// Returns the immediate of a Thumb-2 `MOVW`/`MOVT`, encoded as `imm4:i:imm3:imm8` across both halfwords.
+/// The first halfword is in the low 16 bits of `instruction`.
+fn thumb_imm16(instruction: u32) -> u16 {
+    let imm4 = instruction & 0xF;
+    let i = (instruction >> 10) & 1;
+    let imm3 = (instruction >> 28) & 0x7;
+    let imm8 = (instruction >> 16) & 0xFF;
+    (imm4 << 12 | i << 11 | imm3 << 8 | imm8) as u16
+}
+
+/// Sets the immediate of a Thumb-2 `MOVW`/`MOVT`.
+fn set_thumb_imm16(instruction: u32, imm16: u16) -> u32 {
+    let imm16 = u32::from(imm16);
+    instruction & 0x8F00_FBF0
+        | (imm16 >> 12) & 0xF
+        | ((imm16 >> 11) & 1) << 10
+        | ((imm16 >> 8) & 0x7) << 28
+        | (imm16 & 0xFF) << 16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        map_image::map_image,
+        test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+    };
+
+    /// Returns the RVA of the first fixup, and the offset of the base relocation table.
+    fn first_fixup(image: &[u8]) -> (usize, usize) {
+        let view = PeView::new(image, true).unwrap();
+        let (table, _) = view
+            .data_directory_offset(DataDirectoryType::BaseRelocationTable)
+            .unwrap();
+        let first = view.relocations().unwrap().next().unwrap().unwrap();
+        (first.rva as usize, table)
+    }
+
+    #[test]
+    fn test_apply_relocations_x64() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let original = image.clone();
+        let (fixup, _) = first_fixup(&image);
+        let bytes = &mut image[..];
+        let value = |bytes: &[u8]| u64::from_le_bytes(bytes[fixup..fixup + 8].try_into().unwrap());
+        let before = value(bytes);
+
+        apply_relocations(bytes, 0x1_8000_0000, 0x7FF6_0000_0000).unwrap();
+        assert_eq!(value(bytes), before - 0x1_8000_0000 + 0x7FF6_0000_0000);
+        assert_eq!(PeView::new(bytes, true).unwrap().image_base(), 0x7FF6_0000_0000);
+
+        // And back again.
+        apply_relocations(bytes, 0x7FF6_0000_0000, 0x1_8000_0000).unwrap();
+        assert_eq!(image, original);
+    }
+
+    #[test]
+    fn test_apply_relocations_x86() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X86).unwrap();
+        let (fixup, _) = first_fixup(&image);
+        let bytes = &mut image[..];
+        let value = |bytes: &[u8]| u32::from_le_bytes(bytes[fixup..fixup + 4].try_into().unwrap());
+        let before = value(bytes);
+
+        apply_relocations(bytes, 0x1000_0000, 0x0040_0000).unwrap();
+        assert_eq!(value(bytes), before - 0x1000_0000 + 0x0040_0000);
+        assert_eq!(PeView::new(bytes, true).unwrap().image_base(), 0x0040_0000);
+
+        // Bases above 4 GiB do not fit PE32, and the image is left alone.
+        let before = image.clone();
+        for (old_base, new_base) in [(0x0040_0000, 0x1_0000_0000), (0x1_0000_0000, 0x0040_0000)] {
+            assert_eq!(
+                apply_relocations(&mut image, old_base, new_base),
+                Err(PeError::ImageBaseOutOfRange(0x1_0000_0000))
+            );
+        }
+        assert_eq!(image, before);
+    }
+
+    #[test]
+    fn test_apply_relocations_highadj_and_unsupported() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let (fixup, table) = first_fixup(&image);
+        let page = fixup & !0xFFF;
+        let bytes = &mut image[..];
+
+        // Replace the first two fixups of the first block with a HIGHADJ of 0x1234 at the
+        // start of the page, whose low half is -0x789B.
+        bytes[table + 8..table + 12].copy_from_slice(&[0x00, 0x40, 0x65, 0x87]);
+        bytes[page..page + 2].copy_from_slice(&0x1234u16.to_le_bytes());
+
+        apply_relocations(bytes, 0x1_8000_0000, 0x1_8001_8000).unwrap();
+        assert_eq!(&bytes[page..page + 2], &0x1235u16.to_le_bytes());
+
+        // A MIPS relocation (type 9) is rejected, without touching the image.
+        bytes[table + 8..table + 10].copy_from_slice(&0x9000u16.to_le_bytes());
+        let before = image.clone();
+        let bytes = &mut image[..];
+        assert_eq!(
+            apply_relocations(bytes, 0x1_8001_8000, 0x1_8000_0000),
+            Err(PeError::UnsupportedRelocation(RelocationType::new(9)))
+        );
+        assert_eq!(image, before);
+    }
+
+    #[test]
+    fn test_apply_relocations_malformed_block() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let (_, table) = first_fixup(&image);
+
+        // Make the second block claim to be larger than the table.
+        let first_block = u32::from_le_bytes(image[table + 4..table + 8].try_into().unwrap()) as usize;
+        let second_block = table + first_block;
+        image[second_block + 4..second_block + 8].copy_from_slice(&0x10_0000u32.to_le_bytes());
+
+        let before = image.clone();
+        assert_eq!(
+            apply_relocations(&mut image, 0x1_8000_0000, 0x7FF6_0000_0000),
+            Err(PeError::MalformedRelocation)
+        );
+        assert_eq!(image, before);
+    }
+
+    #[test]
+    fn test_mov32_immediates() {
+        // movw r0, #0x1234
+        let arm = 0xE301_0234;
+        assert_eq!(arm_imm16(arm), 0x1234);
+        assert_eq!(set_arm_imm16(arm, 0xABCD), 0xE30A_0BCD);
+        assert_eq!(arm_imm16(set_arm_imm16(arm, 0xABCD)), 0xABCD);
+
+        // movw r0, #0x1234 (Thumb-2)
+        let thumb = 0x2034_F241;
+        assert_eq!(thumb_imm16(thumb), 0x1234);
+        assert_eq!(thumb_imm16(set_thumb_imm16(thumb, 0xABCD)), 0xABCD);
+        assert_eq!(set_thumb_imm16(thumb, 0x1234), thumb);
+
+        let mut instructions = [0u8; 8];
+        instructions[..4].copy_from_slice(&thumb.to_le_bytes());
+        instructions[4..].copy_from_slice(&0x0000_F2C0u32.to_le_bytes()); // movt r0, #0
+        patch_mov32(&mut instructions, 0, 0x1_0000, thumb_imm16, set_thumb_imm16);
+        assert_eq!(&instructions[..4], &thumb.to_le_bytes());
+        assert_eq!(
+            thumb_imm16(u32::from_le_bytes(instructions[4..].try_into().unwrap())),
+            1
+        );
+    }
+}
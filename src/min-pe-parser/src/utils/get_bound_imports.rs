@@ -0,0 +1,294 @@
+use crate::{error::PeError, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*};
+use alloc::vec::Vec;
+use core::{ffi::c_void, mem::size_of, ptr::read_unaligned};
+
+/// A DLL that the imports of the PE file were bound to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundImport<'a> {
+    /// Name of the DLL.
+    pub name: &'a str,
+    /// Time and date stamp of the DLL the imports were bound to.
+    pub time_date_stamp: u32,
+    /// DLLs that the DLL forwards some of the bound imports to.
+    pub forwarders: Vec<BoundForwarder<'a>>,
+}
+
+/// A DLL that some bound imports were forwarded to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BoundForwarder<'a> {
+    /// Name of the DLL.
+    pub name: &'a str,
+    /// Time and date stamp of the DLL the forwarded imports were bound to.
+    pub time_date_stamp: u32,
+}
+
+impl BoundImport<'_> {
+    /// Checks if the binding is stale, i.e. `export_dll` (which should be the DLL named [`BoundImport::name`])
+    /// is not the build the imports were bound to.
+    pub fn is_stale<W: PeWidth, const CHECKED: bool>(&self, export_dll: &PeHeaders<'_, W, CHECKED>) -> bool {
+        self.time_date_stamp != export_dll.file_header().time_date_stamp()
+    }
+}
+
+impl BoundForwarder<'_> {
+    /// Checks if the binding is stale, i.e. `export_dll` (which should be the DLL named [`BoundForwarder::name`])
+    /// is not the build the forwarded imports were bound to.
+    pub fn is_stale<W: PeWidth, const CHECKED: bool>(&self, export_dll: &PeHeaders<'_, W, CHECKED>) -> bool {
+        self.time_date_stamp != export_dll.file_header().time_date_stamp()
+    }
+}
+
+/// Reads the descriptor or forwarder ref at `offset` in the bound import table.
+fn read_entry<T: Copy>(table: &[u8], offset: usize) -> Result<T, PeError> {
+    let entry = table.get(offset..offset + size_of::<T>()).ok_or(PeError::Truncated)?;
+    // SAFETY: In bounds, and the read is unaligned.
+    Ok(unsafe { read_unaligned(entry.as_ptr() as *const T) })
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Get the DLLs that the imports of the PE file were bound to.
+    ///
+    /// # Returns
+    /// All DLLs in the bound import table, or an empty [`Vec`] if the file has no bound import table.
+    /// [`PeError::Truncated`] is returned if a descriptor or name is not within the directory.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn bound_imports(&self) -> Result<Vec<BoundImport<'a>>, PeError> {
+        // The bound import table usually lives in the headers, which are not in any section,
+        // but are at the same offset in both layouts.
+        let Ok(directory) = self.data_directory(DataDirectoryType::BoundImport) else {
+            return Ok(Vec::new());
+        };
+        let (rva, size) = (directory.virtual_address(), directory.size());
+        let offset = match rva {
+            0 => return Ok(Vec::new()),
+            _ if rva.checked_add(size).is_some_and(|end| end <= self.size_of_headers()) => rva as usize,
+            _ => self.rva_to_offset(rva)?,
+        };
+
+        // Descriptors and names are all within the directory.
+        let table = self.bytes(offset, size as usize)?;
+        let name = |offset: u16| -> Result<&'a str, PeError> {
+            let name = table.get(offset as usize..).ok_or(PeError::Truncated)?;
+            let len = name.iter().position(|&x| x == 0).ok_or(PeError::Truncated)?;
+            core::str::from_utf8(&name[..len]).map_err(|_| PeError::NonUtf8Name)
+        };
+
+        // Descriptors are each followed by their forwarder refs, until a null descriptor.
+        let mut bound_imports = Vec::new();
+        let mut offset = 0;
+        loop {
+            let descriptor: IMAGE_BOUND_IMPORT_DESCRIPTOR = read_entry(table, offset)?;
+            offset += size_of::<IMAGE_BOUND_IMPORT_DESCRIPTOR>();
+            if descriptor.offset_module_name() == 0 {
+                break;
+            }
+
+            let mut forwarders = Vec::with_capacity(if CHECKED {
+                0
+            } else {
+                descriptor.number_of_module_forwarder_refs() as usize
+            });
+
+            for _ in 0..descriptor.number_of_module_forwarder_refs() {
+                let forwarder: IMAGE_BOUND_FORWARDER_REF = read_entry(table, offset)?;
+                offset += size_of::<IMAGE_BOUND_FORWARDER_REF>();
+                forwarders.push(BoundForwarder {
+                    name: name(forwarder.offset_module_name())?,
+                    time_date_stamp: forwarder.time_date_stamp(),
+                });
+            }
+
+            bound_imports.push(BoundImport {
+                name: name(descriptor.offset_module_name())?,
+                time_date_stamp: descriptor.time_date_stamp(),
+                forwarders,
+            });
+        }
+
+        Ok(bound_imports)
+    }
+
+    /// Checks if the imports bound to (or forwarded to) the DLL named `dll_name` are stale,
+    /// i.e. `export_dll` is not the build of `dll_name` that they were bound to.
+    ///
+    /// # Returns
+    /// [`PeError::ImportNotFound`] if nothing was bound to `dll_name`, compared case insensitively.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn is_binding_stale<W2: PeWidth, const CHECKED2: bool>(
+        &self,
+        dll_name: &str,
+        export_dll: &PeHeaders<'_, W2, CHECKED2>,
+    ) -> Result<bool, PeError> {
+        let mut found = false;
+        let mut stale = false;
+        for bound_import in self.bound_imports()? {
+            if bound_import.name.eq_ignore_ascii_case(dll_name) {
+                found = true;
+                stale |= bound_import.is_stale(export_dll);
+            }
+
+            for forwarder in bound_import.forwarders.iter() {
+                if forwarder.name.eq_ignore_ascii_case(dll_name) {
+                    found = true;
+                    stale |= forwarder.is_stale(export_dll);
+                }
+            }
+        }
+
+        found.then_some(stale).ok_or(PeError::ImportNotFound)
+    }
+}
+
+/// Get the DLLs that the imports of the PE file were bound to.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Remarks
+/// This function assumes that the PE file is valid and that the pointer is not null.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// All DLLs in the bound import table, or an empty [`Vec`] if they could not be read.
+/// Use [`try_get_bound_imports`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_bound_imports<'a, W: PeWidth>(pe_start: *const c_void, is_mapped: bool) -> Vec<BoundImport<'a>> {
+    try_get_bound_imports::<W>(pe_start, is_mapped).unwrap_or_default()
+}
+
+/// Get the DLLs that the imports of the PE file were bound to.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// All DLLs in the bound import table, or an empty [`Vec`] if the file has no bound import table.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_bound_imports<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<Vec<BoundImport<'a>>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.bound_imports()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::AutoDetect,
+        utils::{
+            aligned_buffer::AlignedBuffer,
+            map_image::map_image,
+            test_utils::{add_table, directory_entry, RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+        },
+    };
+    use alloc::vec;
+
+    /// Returns the mapped x64 DLL, bound to `KERNEL32.dll` (which forwards to `ntdll.dll`)
+    /// and `USER32.dll` with the given timestamps.
+    fn bind(kernel32: u32, ntdll: u32, user32: u32) -> AlignedBuffer {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        add_table(&mut image, DataDirectoryType::BoundImport, |_| {
+            let entries: [(u32, u16, u16); 4] = [(kernel32, 0x20, 1), (ntdll, 0x2d, 0), (user32, 0x37, 0), (0, 0, 0)];
+            let mut table = Vec::new();
+            for (time_date_stamp, name, refs) in entries {
+                table.extend_from_slice(&time_date_stamp.to_le_bytes());
+                table.extend_from_slice(&name.to_le_bytes());
+                table.extend_from_slice(&refs.to_le_bytes());
+            }
+            table.extend_from_slice(b"KERNEL32.dll\0ntdll.dll\0USER32.dll\0");
+            table
+        });
+        image
+    }
+
+    #[test]
+    fn test_bound_imports() {
+        assert_eq!(
+            unsafe {
+                try_get_bound_imports::<AutoDetect>(RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void, false)
+            },
+            Ok(Vec::new())
+        );
+
+        let expected = vec![
+            BoundImport {
+                name: "KERNEL32.dll",
+                time_date_stamp: 0x1111,
+                forwarders: vec![BoundForwarder {
+                    name: "ntdll.dll",
+                    time_date_stamp: 0x2222,
+                }],
+            },
+            BoundImport {
+                name: "USER32.dll",
+                time_date_stamp: 0x3333,
+                forwarders: vec![],
+            },
+        ];
+        let mut image = bind(0x1111, 0x2222, 0x3333);
+        let imports = unsafe { get_bound_imports::<AutoDetect>(image.as_ptr() as *const c_void, true) };
+        assert_eq!(imports, expected);
+
+        // Linkers put the table in the padding after the section headers, which no section covers.
+        let view = PeView::new(&image, true).unwrap();
+        let table = view.data_directory(DataDirectoryType::BoundImport).unwrap();
+        let (table, size) = (table.virtual_address() as usize, table.size() as usize);
+        let padding =
+            (view.section_headers().as_ptr_range().end as usize - view.pe_start() as usize).next_multiple_of(8);
+        assert!(padding + size <= view.size_of_headers() as usize);
+
+        image.copy_within(table..table + size, padding);
+        let entry = directory_entry(&image, DataDirectoryType::BoundImport);
+        image[entry..entry + 4].copy_from_slice(&(padding as u32).to_le_bytes());
+        assert_eq!(PeView::new(&image, true).unwrap().bound_imports(), Ok(expected));
+    }
+
+    #[test]
+    fn test_is_binding_stale() {
+        let export_dll = PeView::new(RELOADED_BOOTSTRAPPER_DLL_X86, false).unwrap();
+        let time_date_stamp = export_dll.file_header().time_date_stamp();
+
+        let image = bind(time_date_stamp, time_date_stamp + 1, time_date_stamp);
+        let view = PeView::new(&image, true).unwrap();
+
+        assert_eq!(view.is_binding_stale("kernel32.dll", &export_dll), Ok(false));
+        assert_eq!(view.is_binding_stale("NTDLL.DLL", &export_dll), Ok(true));
+        assert_eq!(view.is_binding_stale("USER32.dll", &export_dll), Ok(false));
+        assert_eq!(
+            view.is_binding_stale("SHELL32.dll", &export_dll),
+            Err(PeError::ImportNotFound)
+        );
+        assert!(view.bound_imports().unwrap()[0].forwarders[0].is_stale(&export_dll));
+    }
+
+    #[test]
+    fn test_bound_imports_outside_directory() {
+        let mut image = bind(0x1111, 0x2222, 0x3333);
+        let size = directory_entry(&image, DataDirectoryType::BoundImport) + 4;
+
+        // The last name runs past the end of the directory.
+        image[size..size + 4].copy_from_slice(&0x41u32.to_le_bytes());
+        assert_eq!(
+            PeView::new(&image, true).unwrap().bound_imports(),
+            Err(PeError::Truncated)
+        );
+
+        // The null descriptor is past the end of the directory.
+        image[size..size + 4].copy_from_slice(&0x18u32.to_le_bytes());
+        assert_eq!(
+            PeView::new(&image, true).unwrap().bound_imports(),
+            Err(PeError::Truncated)
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use crate::{error::PeError, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::{ffi::c_void, mem::size_of, ptr::read_unaligned};
+
+/// A CodeView record (`IMAGE_DEBUG_TYPE_CODEVIEW`), identifying the PDB for the file.
+///
+/// A PDB matches the file if its GUID (or signature) and age are the same.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CodeView<'a> {
+    /// A PDB 7.0 record, starting with `RSDS`. Used by all modern toolchains.
+    Rsds {
+        /// Unique ID of the PDB, regenerated on a full link.
+        guid: GUID,
+        /// Incremented every time the PDB is written.
+        age: u32,
+        /// Path to the PDB at the time of linking.
+        path: &'a str,
+    },
+    /// A PDB 2.0 record, starting with `NB10`.
+    Nb10 {
+        /// Offset of the debug info. Always 0, as it lives in a separate PDB.
+        offset: u32,
+        /// Unique ID of the PDB; the time it was created, in seconds since 1970.
+        signature: u32,
+        /// Incremented every time the PDB is written.
+        age: u32,
+        /// Path to the PDB at the time of linking.
+        path: &'a str,
+    },
+}
+
+impl<'a> CodeView<'a> {
+    /// Parses a CodeView record from the data of a debug directory entry.
+    ///
+    /// # Remarks
+    /// The path is read up to the null terminator, or the end of the data.
+    /// `NB10` paths are in the ANSI code page, so only ASCII paths are guaranteed to be read.
+    ///
+    /// # Returns
+    /// The record, or [`PeError::MalformedDebugInfo`] if it is not `RSDS` or `NB10`,
+    /// is truncated, or its path is not UTF-8.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn parse(data: &'a [u8]) -> Result<Self, PeError> {
+        let u32_at = |offset: usize| -> Result<u32, PeError> {
+            let bytes = data.get(offset..offset + 4).ok_or(PeError::MalformedDebugInfo)?;
+            Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        };
+        let path_at = |offset: usize| -> Result<&'a str, PeError> {
+            let path = data.get(offset..).ok_or(PeError::MalformedDebugInfo)?;
+            let path = &path[..path.iter().position(|&x| x == 0).unwrap_or(path.len())];
+            core::str::from_utf8(path).map_err(|_| PeError::MalformedDebugInfo)
+        };
+
+        match data.get(..4) {
+            Some(b"RSDS") => {
+                let guid = data.get(4..4 + size_of::<GUID>()).ok_or(PeError::MalformedDebugInfo)?;
+                Ok(CodeView::Rsds {
+                    // SAFETY: In bounds, and the read is unaligned.
+                    guid: unsafe { read_unaligned(guid.as_ptr() as *const GUID) },
+                    age: u32_at(20)?,
+                    path: path_at(24)?,
+                })
+            }
+            Some(b"NB10") => Ok(CodeView::Nb10 {
+                offset: u32_at(4)?,
+                signature: u32_at(8)?,
+                age: u32_at(12)?,
+                path: path_at(16)?,
+            }),
+            _ => Err(PeError::MalformedDebugInfo),
+        }
+    }
+
+    /// Returns the age of the PDB.
+    pub fn age(&self) -> u32 {
+        match *self {
+            CodeView::Rsds { age, .. } | CodeView::Nb10 { age, .. } => age,
+        }
+    }
+
+    /// Returns the path to the PDB at the time of linking.
+    pub fn path(&self) -> &'a str {
+        match *self {
+            CodeView::Rsds { path, .. } | CodeView::Nb10 { path, .. } => path,
+        }
+    }
+
+    /// Returns the key a symbol server stores the PDB under, i.e. the directory in
+    /// `<server>/<pdb name>/<key>/<pdb name>`.
+    ///
+    /// # Returns
+    /// The GUID (or signature) in uppercase hex without dashes, followed by the age in hex,
+    /// e.g. `DDD321070AD1939A185364D173A6D69B1`.
+    pub fn symbol_server_key(&self) -> String {
+        match *self {
+            CodeView::Rsds { guid, age, .. } => format!("{}{:X}", guid.to_string().replace('-', ""), age),
+            CodeView::Nb10 { signature, age, .. } => format!("{:08X}{:X}", signature, age),
+        }
+    }
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Get the entries of the debug directory.
+    ///
+    /// # Returns
+    /// The entries, or [`PeError::MissingDirectory`] if the file has no debug directory.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn debug_directories(&self) -> Result<&'a [IMAGE_DEBUG_DIRECTORY], PeError> {
+        let (offset, size) = self.data_directory_offset(DataDirectoryType::Debug)?;
+        self.slice(offset, size as usize / size_of::<IMAGE_DEBUG_DIRECTORY>())
+    }
+
+    /// Get the data of a debug directory entry.
+    ///
+    /// # Arguments
+    /// - `entry` - An entry from [`PeHeaders::debug_directories`].
+    ///
+    /// # Remarks
+    /// Some data (e.g. old COFF symbols) is only in the file, and is not loaded into memory.
+    /// For such entries, an empty slice is returned if the file is mapped. Likewise, an empty
+    /// slice is returned for a file whose entry has no file offset.
+    ///
+    /// # Returns
+    /// The data, or a [`PeError`] if it is out of bounds.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn debug_data(&self, entry: &IMAGE_DEBUG_DIRECTORY) -> Result<&'a [u8], PeError> {
+        let size = entry.size_of_data() as usize;
+        let offset = match self.is_mapped() {
+            true if entry.address_of_raw_data() == 0 => return Ok(&[]),
+            true => self.rva_to_offset(entry.address_of_raw_data())?,
+            false if entry.pointer_to_raw_data() == 0 => return Ok(&[]),
+            false => entry.pointer_to_raw_data() as usize,
+        };
+
+        self.bytes(offset, size)
+    }
+
+    /// Finds and parses the CodeView record, which identifies the PDB for the file.
+    ///
+    /// # Returns
+    /// The record, [`PeError::DebugInfoNotFound`] if there is no CodeView entry, or
+    /// [`PeError::MalformedDebugInfo`] if it could not be parsed.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn codeview(&self) -> Result<CodeView<'a>, PeError> {
+        let entry = self
+            .debug_directories()?
+            .iter()
+            .find(|x| x.ty() == DebugType::CODEVIEW)
+            .ok_or(PeError::DebugInfoNotFound)?;
+
+        CodeView::parse(self.debug_data(entry)?)
+    }
+}
+
+/// Get the entries of the debug directory.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The entries, or an empty slice if there are none or they could not be read.
+/// Use [`try_get_debug_directories`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_debug_directories<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> &'a [IMAGE_DEBUG_DIRECTORY] {
+    try_get_debug_directories::<W>(pe_start, is_mapped).unwrap_or_default()
+}
+
+/// Get the entries of the debug directory.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The entries, [`PeError::MissingDirectory`] if the file has no debug directory,
+/// or another [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_debug_directories<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<&'a [IMAGE_DEBUG_DIRECTORY], PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.debug_directories()
+}
+
+/// Finds and parses the CodeView record, which identifies the PDB for the file.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The record, or [`None`] if it could not be read. Use [`try_get_codeview`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_codeview<'a, W: PeWidth>(pe_start: *const c_void, is_mapped: bool) -> Option<CodeView<'a>> {
+    try_get_codeview::<W>(pe_start, is_mapped).ok()
+}
+
+/// Finds and parses the CodeView record, which identifies the PDB for the file.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The record, [`PeError::DebugInfoNotFound`] if there is no CodeView entry,
+/// or another [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_codeview<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<CodeView<'a>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.codeview()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::{AutoDetect, Pe32},
+        utils::{
+            map_image::map_image,
+            test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+        },
+    };
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_debug_directories_x64() {
+        let entries = unsafe {
+            get_debug_directories::<AutoDetect>(RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void, false)
+        };
+        let types: Vec<u32> = entries.iter().map(|x| x.ty().value()).collect();
+        assert_eq!(types, [2, 12, 13, 14, 16]);
+        assert!(entries.iter().all(|x| x.time_date_stamp() == 0xA30C64F8));
+        assert_eq!(entries[0].size_of_data(), 0xAA);
+        assert_eq!(entries[0].address_of_raw_data(), 0x1E7E0);
+        assert_eq!(entries[0].pointer_to_raw_data(), 0x1DBE0);
+
+        // The same data, from the file and once mapped.
+        let file = PeView::new(RELOADED_BOOTSTRAPPER_DLL_X64, false).unwrap();
+        let image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let image = PeView::new(&image, true).unwrap();
+        assert_eq!(file.debug_data(&entries[2]).unwrap().len(), 0x364);
+        assert_eq!(file.debug_data(&entries[2]), image.debug_data(&entries[2]));
+        assert_eq!(image.debug_data(&entries[3]), Ok(&[][..]));
+
+        // An entry with data, but nowhere in the file.
+        let mut entry = entries[0];
+        entry.set_pointer_to_raw_data(0);
+        assert_eq!(file.debug_data(&entry), Ok(&[][..]));
+    }
+
+    #[test]
+    fn test_codeview_x64() {
+        let codeview =
+            unsafe { get_codeview::<AutoDetect>(RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void, false) }
+                .unwrap();
+
+        let CodeView::Rsds { guid, age, path } = codeview else {
+            panic!("expected an RSDS record, got {:?}", codeview);
+        };
+        assert_eq!(guid.to_string(), "DDD32107-0AD1-939A-1853-64D173A6D69B");
+        assert_eq!(age, 1);
+        assert!(path.ends_with(r"\Publish\Loader\X64\Bootstrapper\Reloaded.Mod.Loader.Bootstrapper.pdb"));
+        assert_eq!(codeview.symbol_server_key(), "DDD321070AD1939A185364D173A6D69B1");
+
+        let image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        assert_eq!(PeView::new(&image, true).unwrap().codeview(), Ok(codeview));
+    }
+
+    #[test]
+    fn test_codeview_x86() {
+        let view = PeView::new(RELOADED_BOOTSTRAPPER_DLL_X86, false).unwrap();
+        assert_eq!(view.debug_directories().unwrap().len(), 4);
+
+        let codeview =
+            unsafe { try_get_codeview::<Pe32>(RELOADED_BOOTSTRAPPER_DLL_X86.as_ptr() as *const c_void, false) }
+                .unwrap();
+        assert_eq!(codeview.age(), 1);
+        assert!(codeview
+            .path()
+            .ends_with(r"\X86\Bootstrapper\Reloaded.Mod.Loader.Bootstrapper.pdb"));
+        assert_eq!(codeview.symbol_server_key(), "164949DD97760E0A6481024BE034BA051");
+    }
+
+    #[test]
+    fn test_codeview_parse() {
+        let nb10 = [
+            &b"NB10"[..],
+            &[0; 4],
+            &0x3A5F_1C2Bu32.to_le_bytes(),
+            &[3, 0, 0, 0],
+            b"game.pdb\0",
+        ]
+        .concat();
+        let codeview = CodeView::parse(&nb10).unwrap();
+        assert_eq!(
+            codeview,
+            CodeView::Nb10 {
+                offset: 0,
+                signature: 0x3A5F_1C2B,
+                age: 3,
+                path: "game.pdb"
+            }
+        );
+        assert_eq!(codeview.symbol_server_key(), "3A5F1C2B3");
+
+        // Path without a null terminator.
+        assert_eq!(CodeView::parse(&nb10[..nb10.len() - 5]).unwrap().path(), "game");
+
+        assert_eq!(CodeView::parse(b"RSDS\0\0\0\0"), Err(PeError::MalformedDebugInfo));
+        assert_eq!(CodeView::parse(&nb10[..12]), Err(PeError::MalformedDebugInfo));
+        assert_eq!(CodeView::parse(b"NB09\0\0\0\0"), Err(PeError::MalformedDebugInfo));
+        assert_eq!(
+            CodeView::parse(&[&nb10[..16], b"\xFF.pdb"].concat()),
+            Err(PeError::MalformedDebugInfo)
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use crate::{
+    error::PeError, name_or_id::NameOrId, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*,
+    utils::get_imports::ImportName,
+};
+use alloc::{string::String, vec::Vec};
+use core::{ffi::c_void, mem::size_of};
+
+/// A function delay loaded by the PE file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DelayImportedFunction<'a> {
+    /// The name or ordinal of the function.
+    pub name: ImportName<'a>,
+    /// RVA of the function's slot in the delay load import address table.
+    pub iat_rva: u32,
+    /// RVA of the function's slot in the bound import address table, if the DLL has one.
+    pub bound_iat_rva: Option<u32>,
+}
+
+/// A DLL delay loaded by the PE file, and the functions imported from it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DelayImportedDll<'a> {
+    /// Name of the DLL.
+    pub name: &'a str,
+    /// Functions imported from the DLL, in import name table order.
+    pub functions: Vec<DelayImportedFunction<'a>>,
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Get the names of the DLLs that are delay loaded by the PE file.
+    ///
+    /// # Returns
+    /// Names of all DLLs in the delay load import table, or an empty [`Vec`] if the file
+    /// has no delay load import table.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn delay_import_dll_names(&self) -> Result<Vec<String>, PeError> {
+        let mut dll_names = Vec::new();
+        self.for_each_delay_import_descriptor(|descriptor| {
+            let name_rva = self.delay_import_rva(descriptor, descriptor.dll_name_rva());
+            dll_names.push(self.utf8_str(self.rva_to_offset(name_rva)?)?.into());
+            Ok(())
+        })?;
+
+        Ok(dll_names)
+    }
+
+    /// Get the DLLs delay loaded by the PE file, along with the functions imported from each.
+    ///
+    /// # Remarks
+    /// Both the current (RVA based) and the legacy Visual C++ 6.0 (VA based) layouts are supported;
+    /// the returned RVAs are always RVAs.
+    ///
+    /// # Returns
+    /// All DLLs in the delay load import table, or an empty [`Vec`] if the file
+    /// has no delay load import table.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn delay_imports(&self) -> Result<Vec<DelayImportedDll<'a>>, PeError> {
+        let mut dlls = Vec::new();
+        self.for_each_delay_import_descriptor(|descriptor| {
+            let name_rva = self.delay_import_rva(descriptor, descriptor.dll_name_rva());
+            dlls.push(DelayImportedDll {
+                name: self.utf8_str(self.rva_to_offset(name_rva)?)?,
+                functions: self.delay_imported_functions(descriptor)?,
+            });
+            Ok(())
+        })?;
+
+        Ok(dlls)
+    }
+
+    /// Calls `f` with each delay load import descriptor, until the null descriptor.
+    fn for_each_delay_import_descriptor(
+        &self,
+        mut f: impl FnMut(&IMAGE_DELAYLOAD_DESCRIPTOR) -> Result<(), PeError>,
+    ) -> Result<(), PeError> {
+        // Determine the start and end of the delay load descriptors array.
+        let Some((descriptors, size)) =
+            self.data_directory_offset_if_present(DataDirectoryType::DelayImportDescriptor)?
+        else {
+            return Ok(());
+        };
+        let num_descriptors = size as usize / size_of::<IMAGE_DELAYLOAD_DESCRIPTOR>();
+
+        for x in 0..num_descriptors {
+            let descriptor: IMAGE_DELAYLOAD_DESCRIPTOR =
+                self.read(descriptors + x * size_of::<IMAGE_DELAYLOAD_DESCRIPTOR>())?;
+
+            // A null descriptor terminates the table.
+            if descriptor.dll_name_rva() == 0 {
+                break;
+            }
+
+            f(&descriptor)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the import name table of a delay load descriptor, pairing each thunk with its IAT slots.
+    fn delay_imported_functions(
+        &self,
+        descriptor: &IMAGE_DELAYLOAD_DESCRIPTOR,
+    ) -> Result<Vec<DelayImportedFunction<'a>>, PeError> {
+        let name_table = self.rva_to_offset(self.delay_import_rva(descriptor, descriptor.import_name_table_rva()))?;
+        let iat_rva = self.delay_import_rva(descriptor, descriptor.import_address_table_rva());
+        let bound_iat_rva = self.delay_import_rva(descriptor, descriptor.bound_import_address_table_rva());
+        let thunk_size = self.thunk_size();
+
+        let mut functions = Vec::new();
+        for x in 0.. {
+            // A null thunk terminates the table.
+            let name = match self.lookup_thunk(name_table + x * thunk_size)? {
+                None => break,
+                Some(NameOrId::Id(ordinal)) => NameOrId::Id(ordinal),
+                Some(NameOrId::Name(hint_name_rva)) => {
+                    self.import_by_name(self.delay_import_rva(descriptor, hint_name_rva))?
+                }
+            };
+
+            let slot_offset = (x * thunk_size) as u32;
+            functions.push(DelayImportedFunction {
+                name,
+                iat_rva: iat_rva.wrapping_add(slot_offset),
+                bound_iat_rva: (bound_iat_rva != 0).then(|| bound_iat_rva.wrapping_add(slot_offset)),
+            });
+        }
+
+        Ok(functions)
+    }
+
+    /// Converts an address from a delay load descriptor (or its name table) to an RVA.
+    /// Legacy descriptors hold VAs, based on the preferred image base.
+    #[inline]
+    fn delay_import_rva(&self, descriptor: &IMAGE_DELAYLOAD_DESCRIPTOR, value: u32) -> u32 {
+        if descriptor.is_rva_based() || value == 0 {
+            value
+        } else {
+            value.wrapping_sub(self.image_base() as u32)
+        }
+    }
+}
+
+/// Get the names of the DLLs that are delay loaded by the PE file.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Remarks
+/// This function assumes that the PE file is valid and that the pointer is not null.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+/// Names of all DLLs in the delay load import table, or an empty [`Vec`] if they could not be read.
+/// Use [`try_get_delay_import_dll_names`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_delay_import_dll_names<W: PeWidth>(pe_start: *const c_void, is_mapped: bool) -> Vec<String> {
+    try_get_delay_import_dll_names::<W>(pe_start, is_mapped).unwrap_or_default()
+}
+
+/// Get the names of the DLLs that are delay loaded by the PE file.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+/// Names of all DLLs in the delay load import table, or an empty [`Vec`] if the file
+/// has no delay load import table.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_delay_import_dll_names<W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<Vec<String>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.delay_import_dll_names()
+}
+
+/// Get the DLLs delay loaded by the PE file, along with the functions imported from each.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Remarks
+/// See [`get_delay_import_dll_names`].
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// All DLLs in the delay load import table, or an empty [`Vec`] if they could not be read.
+/// Use [`try_get_delay_imports`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_delay_imports<'a, W: PeWidth>(pe_start: *const c_void, is_mapped: bool) -> Vec<DelayImportedDll<'a>> {
+    try_get_delay_imports::<W>(pe_start, is_mapped).unwrap_or_default()
+}
+
+/// Get the DLLs delay loaded by the PE file, along with the functions imported from each.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// All DLLs in the delay load import table, or an empty [`Vec`] if the file
+/// has no delay load import table.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_delay_imports<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<Vec<DelayImportedDll<'a>>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.delay_imports()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::AutoDetect,
+        utils::{
+            aligned_buffer::AlignedBuffer,
+            get_imports::HintName,
+            map_image::map_image,
+            test_utils::{add_table, RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+        },
+    };
+    use alloc::vec;
+
+    /// Appends a delay load import table for `d3dcompiler_47.dll` to a mapped image,
+    /// importing `D3DCompile` by name and ordinal 5. Returns the RVA of the table.
+    fn add_delay_imports(image: &mut AlignedBuffer, rva_based: bool) -> u32 {
+        let headers = PeView::new(image, true).unwrap();
+        let (image_base, thunk_size) = (headers.image_base() as u32, headers.thunk_size());
+
+        add_table(image, DataDirectoryType::DelayImportDescriptor, |table| {
+            // Legacy tables hold VAs.
+            let address = |rva: u32| if rva_based { rva } else { rva + image_base };
+            let thunk = |value: u64| value.to_le_bytes()[..thunk_size].to_vec();
+            let ordinal = 1u64 << (thunk_size * 8 - 1);
+
+            let mut bytes = [
+                rva_based as u32,
+                address(table + 0x40),
+                address(table + 0x60),
+                address(table + 0xa0),
+                address(table + 0x80),
+                address(table + 0xc0),
+                0,
+                0,
+            ]
+            .map(u32::to_le_bytes)
+            .concat();
+            bytes.resize(0x40, 0);
+            bytes.extend_from_slice(b"d3dcompiler_47.dll\0");
+            bytes.resize(0x60, 0);
+            bytes.extend_from_slice(&[&3u16.to_le_bytes()[..], b"D3DCompile\0"].concat());
+            bytes.resize(0x80, 0);
+            bytes.extend_from_slice(&[thunk(address(table + 0x60).into()), thunk(ordinal | 5)].concat());
+            bytes.resize(0x100, 0);
+            bytes
+        })
+    }
+
+    #[test]
+    fn test_delay_imports_rva_based_x64() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let pe_start = image.as_ptr() as *const c_void;
+        assert_eq!(
+            unsafe { try_get_delay_imports::<AutoDetect>(pe_start, true) },
+            Ok(Vec::new())
+        );
+
+        let table = add_delay_imports(&mut image, true);
+        let pe_start = image.as_ptr() as *const c_void;
+        assert_eq!(
+            unsafe { get_delay_import_dll_names::<AutoDetect>(pe_start, true) },
+            vec!["d3dcompiler_47.dll"]
+        );
+        assert_eq!(
+            unsafe { get_delay_imports::<AutoDetect>(pe_start, true) },
+            vec![DelayImportedDll {
+                name: "d3dcompiler_47.dll",
+                functions: vec![
+                    DelayImportedFunction {
+                        name: NameOrId::Name(HintName {
+                            hint: 3,
+                            name: "D3DCompile"
+                        }),
+                        iat_rva: table + 0xa0,
+                        bound_iat_rva: Some(table + 0xc0),
+                    },
+                    DelayImportedFunction {
+                        name: NameOrId::Id(5),
+                        iat_rva: table + 0xa8,
+                        bound_iat_rva: Some(table + 0xc8),
+                    },
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_delay_imports_va_based_x86() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X86).unwrap();
+        let table = add_delay_imports(&mut image, false);
+
+        let imports = PeView::new(&image, true).unwrap().delay_imports().unwrap();
+        assert_eq!(imports[0].name, "d3dcompiler_47.dll");
+        assert_eq!(
+            imports[0].functions[0].name,
+            NameOrId::Name(HintName {
+                hint: 3,
+                name: "D3DCompile"
+            })
+        );
+        assert_eq!(imports[0].functions[1].name, NameOrId::Id(5));
+        assert_eq!(imports[0].functions[1].iat_rva, table + 0xa4);
+        assert_eq!(imports[0].functions[1].bound_iat_rva, Some(table + 0xc4));
+    }
+}
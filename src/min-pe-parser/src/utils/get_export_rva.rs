@@ -1,6 +1,213 @@
-use super::common::*;
-use crate::{prelude::*, types::*};
-use core::{ffi::c_void, ptr};
+use crate::{error::PeError, name_or_id::NameOrId, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*};
+use core::{cmp::Ordering, ffi::c_void, mem::size_of};
+use simple_endian::LittleEndian;
+
+/// What an export refers to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExportTarget<'a> {
+    /// Code or data in the PE file, at this RVA.
+    Code(u32),
+    /// An export of another DLL, which the loader resolves instead.
+    Forwarder {
+        /// Name of the DLL, without the `.dll` extension, e.g. `NTDLL`.
+        dll: &'a str,
+        /// The export of the DLL, e.g. `RtlAllocateHeap` or `#123`.
+        name_or_ordinal: NameOrId<&'a str>,
+    },
+    /// A forwarded export whose forwarder string, at this RVA, could not be parsed.
+    ///
+    /// Only listed by [`PeHeaders::exports`], so that no entry is lost;
+    /// [`PeHeaders::export_target`] returns [`PeError::MalformedForwarder`] instead.
+    MalformedForwarder(u32),
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Returns the export directory.
+    ///
+    /// # Returns
+    /// [`PeError::MissingDirectory`] if the file has no export table.
+    pub fn export_directory(&self) -> Result<IMAGE_EXPORT_DIRECTORY, PeError> {
+        let (export_directory, _) = self.data_directory_offset(DataDirectoryType::ExportTable)?;
+        self.read(export_directory)
+    }
+
+    /// Retrieves the offset of the given export in Virtual Memory,
+    /// relative to the start of the PE file.
+    ///
+    /// # Arguments
+    /// - `export_name` - The name of the export to search.
+    ///
+    /// # Returns
+    /// The RVA of the export, [`PeError::ExportNotFound`] if the file has no such export,
+    /// or another [`PeError`] if the export table is malformed.
+    ///
+    /// For forwarded exports this is the RVA of the forwarder string; use [`PeHeaders::export`]
+    /// to tell them apart.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn export_rva(&self, export_name: &str) -> Result<usize, PeError> {
+        // Get the export directory.
+        let export_directory = self.export_directory()?;
+
+        // We now need to iterate through the export name pointer table.
+        // Note: The exports are lexically ordered, so this could be a binary search,
+        //       however for our purposes, we assume minimal/near zero exports,
+        //       so saving on code size is more beneficial.
+        //       For large export tables, see `export_rva_binary_search`.
+        let export_name_pointer_table = self.rva_to_offset(export_directory.address_of_names())?;
+
+        for x in 0..export_directory.number_of_names() as usize {
+            // Ignore if the name couldn't be read.
+            let name = match self.export_name(export_name_pointer_table, x) {
+                Ok(name) => name,
+                Err(_) => continue,
+            };
+
+            // Compare the export name with the given export name.
+            // Note: The exports are ASCII, so we compare the bytes without UTF-8 validation.
+            if name != export_name.as_bytes() {
+                continue;
+            }
+
+            return self.export_rva_by_name_index(&export_directory, x);
+        }
+
+        // Couldn't find the export
+        Err(PeError::ExportNotFound)
+    }
+
+    /// Same as [`PeHeaders::export_rva`], but uses a binary search over the export names,
+    /// which the linker sorts lexically. This is O(log n) rather than O(n), so is preferable
+    /// for DLLs with many exports, at the cost of slightly more code.
+    ///
+    /// # Arguments
+    /// - `export_name` - The name of the export to search.
+    ///
+    /// # Returns
+    /// The RVA of the export, [`PeError::ExportNotFound`] if the file has no such export,
+    /// or another [`PeError`] if the export table is malformed.
+    pub fn export_rva_binary_search(&self, export_name: &str) -> Result<usize, PeError> {
+        let export_directory = self.export_directory()?;
+        let export_name_pointer_table = self.rva_to_offset(export_directory.address_of_names())?;
+
+        let mut low = 0;
+        let mut high = export_directory.number_of_names() as usize;
+        while low < high {
+            let x = low + (high - low) / 2;
+            match self
+                .export_name(export_name_pointer_table, x)?
+                .cmp(export_name.as_bytes())
+            {
+                Ordering::Less => low = x + 1,
+                Ordering::Greater => high = x,
+                Ordering::Equal => return self.export_rva_by_name_index(&export_directory, x),
+            }
+        }
+
+        Err(PeError::ExportNotFound)
+    }
+
+    /// Returns the name at index `x` of the export name pointer table, at offset `export_name_pointer_table`.
+    #[inline]
+    pub(crate) fn export_name(&self, export_name_pointer_table: usize, x: usize) -> Result<&'a [u8], PeError> {
+        let export_name_rva: LittleEndian<u32> = self.read(export_name_pointer_table + x * size_of::<u32>())?;
+        self.c_str(self.rva_to_offset(export_name_rva.into())?)
+    }
+
+    /// Returns the RVA of the export whose name is at index `x` of the export name pointer table.
+    pub(crate) fn export_rva_by_name_index(
+        &self,
+        export_directory: &IMAGE_EXPORT_DIRECTORY,
+        x: usize,
+    ) -> Result<usize, PeError> {
+        // Imitating what Microsoft specifies in docs:
+        // https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#export-ordinal-table
+        // i = Search_ExportNamePointerTable (name);
+        // ordinal = ExportOrdinalTable [i];
+        // rva = ExportAddressTable [ordinal];
+
+        // Get the ordinal of the export.
+        // The ordinal value is actually the index in the export address table.
+        let ordinal_table = self.rva_to_offset(export_directory.address_of_name_ordinals())?;
+        let ordinal: LittleEndian<u16> = self.read(ordinal_table + x * size_of::<u16>())?;
+        let ordinal = u16::from(ordinal) as usize;
+        if CHECKED && ordinal >= export_directory.number_of_functions() as usize {
+            return Err(PeError::Truncated);
+        }
+
+        // Use the ordinal to get the export address.
+        let export_address_table = self.rva_to_offset(export_directory.address_of_functions())?;
+        let export_address_rva: LittleEndian<u32> = self.read(export_address_table + ordinal * size_of::<u32>())?;
+        Ok(u32::from(export_address_rva) as usize)
+    }
+
+    /// Retrieves the offset of the export with the given ordinal in Virtual Memory,
+    /// relative to the start of the PE file.
+    ///
+    /// # Arguments
+    /// - `ordinal` - The ordinal of the export, as passed to `GetProcAddress` via `MAKEINTRESOURCE`.
+    ///
+    /// # Returns
+    /// The RVA of the export, [`PeError::ExportNotFound`] if the file has no such export,
+    /// or another [`PeError`] if the export table is malformed.
+    ///
+    /// For forwarded exports this is the RVA of the forwarder string; use [`PeHeaders::export`]
+    /// to tell them apart.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn export_rva_by_ordinal(&self, ordinal: u16) -> Result<usize, PeError> {
+        let export_directory = self.export_directory()?;
+
+        // The ordinal is biased by the base, the index into the export address table is not.
+        let index = (ordinal as u32).wrapping_sub(export_directory.base()) as usize;
+        if index >= export_directory.number_of_functions() as usize {
+            return Err(PeError::ExportNotFound);
+        }
+
+        // Unused entries are zero.
+        let export_address_table = self.rva_to_offset(export_directory.address_of_functions())?;
+        let export_address_rva: LittleEndian<u32> = self.read(export_address_table + index * size_of::<u32>())?;
+        match u32::from(export_address_rva) {
+            0 => Err(PeError::ExportNotFound),
+            rva => Ok(rva as usize),
+        }
+    }
+
+    /// Looks up an export by name or ordinal, and determines whether it is forwarded to another DLL.
+    ///
+    /// # Returns
+    /// What the export refers to, [`PeError::ExportNotFound`] if the file has no such export,
+    /// or another [`PeError`] if the export table is malformed.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn export(&self, export: NameOrId<&str>) -> Result<ExportTarget<'a>, PeError> {
+        let rva = match export {
+            NameOrId::Name(name) => self.export_rva(name)?,
+            NameOrId::Id(ordinal) => self.export_rva_by_ordinal(ordinal)?,
+        };
+
+        self.export_target(rva as u32)
+    }
+
+    /// Determines what the export with the given RVA refers to.
+    ///
+    /// # Remarks
+    /// An export is forwarded if its RVA lies within the export directory,
+    /// in which case it points to a forwarder string such as `NTDLL.RtlAllocateHeap`.
+    pub fn export_target(&self, rva: u32) -> Result<ExportTarget<'a>, PeError> {
+        let directory = self.data_directory(DataDirectoryType::ExportTable)?;
+        if rva.wrapping_sub(directory.virtual_address()) >= directory.size() {
+            return Ok(ExportTarget::Code(rva));
+        }
+
+        // The DLL name may contain dots, the function name may not.
+        let forwarder = self.utf8_str(self.rva_to_offset(rva)?)?;
+        let (dll, function) = forwarder.rsplit_once('.').ok_or(PeError::MalformedForwarder)?;
+        let name_or_ordinal = match function.strip_prefix('#') {
+            Some(ordinal) => NameOrId::Id(ordinal.parse().map_err(|_| PeError::MalformedForwarder)?),
+            None => NameOrId::Name(function),
+        };
+
+        Ok(ExportTarget::Forwarder { dll, name_or_ordinal })
+    }
+}
 
 /// Retrieves the offset of the given export in Virtual Memory,
 /// relative to the start of the PE file. Add the returned value to the address
@@ -10,8 +217,6 @@ use core::{ffi::c_void, ptr};
 /// - `pe_start` - A pointer to the start of the PE file in memory.
 /// - `export_name` - The name of the export to search.
 /// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
-/// - `force_pe64` - Force PE64 format. Saves a few branches in code.
-/// - `force_pe32` - Force PE32 format. Saves a few branches in code.
 ///
 /// # Safety
 /// We dajiobu if pe_start is a valid pointer and the PE file is valid.
@@ -20,153 +225,194 @@ use core::{ffi::c_void, ptr};
 ///
 /// A value of [`usize::MAX`] indicates that the export was not found.
 /// Otherwise, the return value is the absolute offset of the export in memory.
+///
+/// Use [`try_get_export_rva`] to find out why an export could not be found.
+/// If looking up multiple exports, create a [`PeHeaders`] once and use [`PeHeaders::export_rva`].
 #[cfg_attr(feature = "size_opt", optimize(size))]
-pub unsafe fn get_export_rva(
+pub unsafe fn get_export_rva<W: PeWidth>(pe_start: *const c_void, export_name: &str, is_mapped: bool) -> usize {
+    try_get_export_rva::<W>(pe_start, export_name, is_mapped).unwrap_or(usize::MAX)
+}
+
+/// Retrieves the offset of the given export in Virtual Memory,
+/// relative to the start of the PE file. Add the returned value to the address
+/// of the mapped PE file to get the absolute address of the export.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `export_name` - The name of the export to search.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+///
+/// The absolute offset of the export in memory, [`PeError::ExportNotFound`] if the
+/// file has no such export, or another [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_export_rva<W: PeWidth>(
     pe_start: *const c_void,
     export_name: &str,
     is_mapped: bool,
-    force_pe64: bool,
-    force_pe32: bool,
-) -> usize {
-    assert_pe_is_aligned(pe_start);
-
-    // Validate PE file signature and setup initial pointer.
-    let pe_start = pe_start as PIMAGE_DOS_HEADER;
-
-    if (*pe_start).e_magic() != 0x00005A4D {
-        return usize::MAX;
-    }
-
-    // Get the NT Header.
-    let dos_header = pe_start;
-    let nt_headers_ptr = (pe_start as pu8).add((*dos_header).e_lfanew() as usize) as pu32;
-
-    // Access the IMAGE_FILE_HEADER to retrieve the number of sections and other metadata.
-    let file_header = nt_headers_ptr.add(0x1) as PIMAGE_FILE_HEADER;
-    let optional_header_ptr = file_header.add(1) as PIMAGE_OPTIONAL_HEADER32;
-
-    // Determine the actual PE format (PE32 or PE64).
-    let is_pe64 = force_pe64 || (*optional_header_ptr).magic().is_pe64();
-    let is_pe32 = force_pe32 || (*optional_header_ptr).magic().is_pe32();
-
-    // Calculate the number of data directories to access the import table directory.
-    let num_rva_sizes = get_num_rva_and_sizes(optional_header_ptr, is_pe64, is_pe32) as isize;
-
-    // Locate the data directories, specifically the export table directory, using the optional header.
-    let data_directories_ptr = get_data_directories_ptr(optional_header_ptr as pu8, is_pe64, is_pe32);
-    let export_table_directory = &*data_directories_ptr.offset(DataDirectoryType::ExportTable as isize);
-
-    // Get the section headers to calculate the absolute offset of the import descriptor.
-    let section_headers = (data_directories_ptr).offset(num_rva_sizes) as pu8;
-
-    // WARNING !!
-    //   Rust does not allow unaligned slices, to prevent unaligned reads.
-    //   What we're doing here is undefined behaviour.
-    //   However, because we can guarantee that the members of the struct are aligned, even if
-    //   the struct itself isn't, we should be ok.
-    let section_headers = &*ptr::slice_from_raw_parts(
-        section_headers as PIMAGE_SECTION_HEADER,
-        (*file_header).number_of_sections().into(),
-    );
-
-    // Translate the RVA of the import descriptor to an absolute offset in memory.
-    let export_descriptor_abs_offset =
-        rva_to_absolute_offset(export_table_directory.virtual_address(), section_headers, is_mapped);
-
-    // Early exit if the absolute offset of the export descriptors could not be calculated.
-    if export_descriptor_abs_offset.is_none() {
-        return usize::MAX;
-    }
-
-    let export_descriptor_abs_offset = export_descriptor_abs_offset.unwrap_unchecked() as usize;
-    let export_descriptor = (pe_start as pu8).add(export_descriptor_abs_offset) as PIMAGE_EXPORT_DIRECTORY;
-
-    // Get the ordinal 'base'
-    let num_names = (*export_descriptor).number_of_names();
-
-    // We now need to iterate through the export name pointer table.
-    // Note: This could be optimized as the exports are lexically ordered,
-    //       however for our purposes, we assume minimal/near zero exports,
-    //       so saving on code size is more beneficial.
-
-    // Get the address of the export name pointer table.
-    let export_name_pointer_table_rva =
-        rva_to_absolute_offset((*export_descriptor).address_of_names(), section_headers, is_mapped);
-
-    // Early exit if the absolute offset of the export name pointer table could not be calculated.
-    if export_name_pointer_table_rva.is_none() {
-        return usize::MAX;
-    }
-
-    // Iterate through the export name pointer table to find the export.
-    let export_name_pointer_table =
-        (pe_start as pu8).add(export_name_pointer_table_rva.unwrap_unchecked() as usize) as pu32_le;
-
-    for x in 0..num_names {
-        let export_name_rva = *export_name_pointer_table.add(x as usize);
-        let export_name_ptr = rva_to_absolute_offset(export_name_rva.into(), section_headers, is_mapped);
-
-        // Ignore if the offset couldn't be calculated.
-        if export_name_ptr.is_none() {
-            continue;
-        }
+) -> Result<usize, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.export_rva(export_name)
+}
 
-        // Get that export name.
-        let export_name_ptr = (pe_start as pu8).add(export_name_ptr.unwrap_unchecked() as usize);
-
-        // Compare the export name with the given export name.
-        // Note: The exports are ASCII,
-        if get_null_terminated_utf8_string_slice(export_name_ptr) == export_name {
-            // Imitating what Microsoft specifies in docs:
-            // https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#export-ordinal-table
-            // i = Search_ExportNamePointerTable (name);
-            // ordinal = ExportOrdinalTable [i];
-            // rva = ExportAddressTable [ordinal];
-
-            // Get the ordinal of the export.
-            // The ordinal value is actually the index in the export address table.
-            let ordinal_table_rva = rva_to_absolute_offset(
-                (*export_descriptor).address_of_name_ordinals(),
-                section_headers,
-                is_mapped,
-            );
-
-            if ordinal_table_rva.is_none() {
-                return usize::MAX;
-            }
+/// Same as [`get_export_rva`], but uses a binary search over the export names.
+/// This is O(log n) rather than O(n), so is preferable for DLLs with many exports,
+/// at the cost of slightly more code.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `export_name` - The name of the export to search.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+///
+/// A value of [`usize::MAX`] indicates that the export was not found.
+/// Otherwise, the return value is the absolute offset of the export in memory.
+///
+/// Use [`try_get_export_rva_binary_search`] to find out why an export could not be found.
+pub unsafe fn get_export_rva_binary_search<W: PeWidth>(
+    pe_start: *const c_void,
+    export_name: &str,
+    is_mapped: bool,
+) -> usize {
+    try_get_export_rva_binary_search::<W>(pe_start, export_name, is_mapped).unwrap_or(usize::MAX)
+}
 
-            let ordinal_table_rva = ordinal_table_rva.unwrap_unchecked() as usize;
-            let ordinal_table = (pe_start as pu8).add(ordinal_table_rva) as pu16_le;
+/// Same as [`try_get_export_rva`], but uses a binary search over the export names.
+/// This is O(log n) rather than O(n), so is preferable for DLLs with many exports,
+/// at the cost of slightly more code.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `export_name` - The name of the export to search.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+///
+/// The absolute offset of the export in memory, [`PeError::ExportNotFound`] if the
+/// file has no such export, or another [`PeError`] if the file is not a valid PE.
+pub unsafe fn try_get_export_rva_binary_search<W: PeWidth>(
+    pe_start: *const c_void,
+    export_name: &str,
+    is_mapped: bool,
+) -> Result<usize, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.export_rva_binary_search(export_name)
+}
 
-            // Look up the correct ordinal using the index.
-            let ordinal: u16 = (*ordinal_table.add(x as usize)).into();
+/// Retrieves the offset of the export with the given ordinal in Virtual Memory,
+/// relative to the start of the PE file. Add the returned value to the address
+/// of the mapped PE file to get the absolute address of the export.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `ordinal` - The ordinal of the export, as passed to `GetProcAddress` via `MAKEINTRESOURCE`.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+///
+/// A value of [`usize::MAX`] indicates that the export was not found.
+/// Otherwise, the return value is the absolute offset of the export in memory.
+///
+/// Use [`try_get_export_rva_by_ordinal`] to find out why an export could not be found.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_export_rva_by_ordinal<W: PeWidth>(pe_start: *const c_void, ordinal: u16, is_mapped: bool) -> usize {
+    try_get_export_rva_by_ordinal::<W>(pe_start, ordinal, is_mapped).unwrap_or(usize::MAX)
+}
 
-            // Get the address of the export address table.
-            let export_address_table_rva =
-                rva_to_absolute_offset((*export_descriptor).address_of_functions(), section_headers, is_mapped);
-            if export_address_table_rva.is_none() {
-                return usize::MAX;
-            }
-            let export_address_table =
-                (pe_start as pu8).add(export_address_table_rva.unwrap_unchecked() as usize) as pu32_le;
+/// Retrieves the offset of the export with the given ordinal in Virtual Memory,
+/// relative to the start of the PE file. Add the returned value to the address
+/// of the mapped PE file to get the absolute address of the export.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `ordinal` - The ordinal of the export, as passed to `GetProcAddress` via `MAKEINTRESOURCE`.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+///
+/// The absolute offset of the export in memory, [`PeError::ExportNotFound`] if the
+/// file has no such export, or another [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_export_rva_by_ordinal<W: PeWidth>(
+    pe_start: *const c_void,
+    ordinal: u16,
+    is_mapped: bool,
+) -> Result<usize, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.export_rva_by_ordinal(ordinal)
+}
 
-            // Use the actual ordinal (adjusted by the base) to get the export address.
-            let export_address_rva = *export_address_table.add(ordinal as usize);
-            return u32::from(export_address_rva) as usize;
-        }
-    }
+/// Looks up an export by name or ordinal, and determines whether it is forwarded to another DLL.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `export` - The name or ordinal of the export to search.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// What the export refers to, or [`None`] if the export was not found.
+/// Use [`try_get_export`] to find out why an export could not be found.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_export<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    export: NameOrId<&str>,
+    is_mapped: bool,
+) -> Option<ExportTarget<'a>> {
+    try_get_export::<W>(pe_start, export, is_mapped).ok()
+}
 
-    // Couldn't find the export
-    usize::MAX
+/// Looks up an export by name or ordinal, and determines whether it is forwarded to another DLL.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `export` - The name or ordinal of the export to search.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// What the export refers to, [`PeError::ExportNotFound`] if the file has no such export,
+/// or another [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_export<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    export: NameOrId<&str>,
+    is_mapped: bool,
+) -> Result<ExportTarget<'a>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.export(export)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::utils::{
-        get_export_rva::get_export_rva,
-        test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::{AutoDetect, Pe32, Pe64},
+        utils::{
+            aligned_buffer::AlignedBuffer,
+            test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+        },
     };
-    use core::ffi::c_void;
 
     // Define a struct to hold test data for export names and their expected RVAs.
     struct ExportTestData {
@@ -195,7 +441,10 @@ mod tests {
         ];
 
         for export in exports.iter() {
-            let offset = unsafe { get_export_rva(pe_start, export.name, false, true, false) };
+            let offset = unsafe { get_export_rva::<Pe64>(pe_start, export.name, false) };
+            assert_eq!(offset, export.expected_rva, "Mismatch for export {}", export.name);
+
+            let offset = unsafe { get_export_rva_binary_search::<Pe64>(pe_start, export.name, false) };
             assert_eq!(offset, export.expected_rva, "Mismatch for export {}", export.name);
         }
     }
@@ -215,8 +464,116 @@ mod tests {
         ];
 
         for export in exports.iter() {
-            let offset = unsafe { get_export_rva(pe_start, export.name, false, false, true) };
+            let offset = unsafe { get_export_rva::<Pe32>(pe_start, export.name, false) };
+            assert_eq!(offset, export.expected_rva, "Mismatch for export {}", export.name);
+
+            let offset = unsafe { get_export_rva_binary_search::<Pe32>(pe_start, export.name, false) };
             assert_eq!(offset, export.expected_rva, "Mismatch for export {}", export.name);
         }
     }
+
+    #[test]
+    fn test_try_get_export_rva_errors() {
+        let pe_start = RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void;
+        let result = unsafe { try_get_export_rva::<AutoDetect>(pe_start, "DoesNotExist", false) };
+        assert_eq!(result, Err(PeError::ExportNotFound));
+        for name in ["A", "DoesNotExist", "SB", "zzz"] {
+            let result = unsafe { try_get_export_rva_binary_search::<AutoDetect>(pe_start, name, false) };
+            assert_eq!(result, Err(PeError::ExportNotFound));
+        }
+
+        let not_a_pe = [0u32; 64];
+        let pe_start = not_a_pe.as_ptr() as *const c_void;
+        let result = unsafe { try_get_export_rva::<AutoDetect>(pe_start, "InitializeASI", false) };
+        assert_eq!(result, Err(PeError::InvalidDosMagic));
+        assert_eq!(
+            unsafe { get_export_rva::<AutoDetect>(pe_start, "InitializeASI", false) },
+            usize::MAX
+        );
+    }
+
+    #[test]
+    fn test_get_export_rva_by_ordinal() {
+        let pe_start = RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void;
+
+        // The ordinal base is 1.
+        assert_eq!(unsafe { get_export_rva_by_ordinal::<Pe64>(pe_start, 1, false) }, 0x236c);
+        assert_eq!(
+            unsafe { get_export_rva_by_ordinal::<Pe64>(pe_start, 8, false) },
+            0x10940
+        );
+        assert_eq!(
+            unsafe { get_export_rva_by_ordinal::<Pe64>(pe_start, 0, false) },
+            usize::MAX
+        );
+        assert_eq!(
+            unsafe { try_get_export_rva_by_ordinal::<AutoDetect>(pe_start, 9, false) },
+            Err(PeError::ExportNotFound)
+        );
+
+        let pe_start = RELOADED_BOOTSTRAPPER_DLL_X86.as_ptr() as *const c_void;
+        assert_eq!(unsafe { get_export_rva_by_ordinal::<Pe32>(pe_start, 8, false) }, 0xb1b0);
+    }
+
+    #[test]
+    fn test_export_rva_by_ordinal_with_base_and_unused_entries() {
+        let mut bytes = AlignedBuffer::from(RELOADED_BOOTSTRAPPER_DLL_X64);
+
+        // Ordinal base 10, and leave the second entry unused.
+        let view = PeView::new(&bytes, false).unwrap();
+        let (directory, _) = view.data_directory_offset(DataDirectoryType::ExportTable).unwrap();
+        let mut export_directory = view.export_directory().unwrap();
+        let export_address_table = view.rva_to_offset(export_directory.address_of_functions()).unwrap();
+        export_directory.set_base(10);
+        unsafe { (bytes.as_mut_ptr().add(directory) as *mut IMAGE_EXPORT_DIRECTORY).write_unaligned(export_directory) };
+        bytes[export_address_table + 4..export_address_table + 8].fill(0);
+
+        let view = PeView::new(&bytes, false).unwrap();
+        assert_eq!(view.export_rva_by_ordinal(10), Ok(0x236c));
+        assert_eq!(view.export_rva_by_ordinal(11), Err(PeError::ExportNotFound));
+        assert_eq!(view.export_rva_by_ordinal(17), Ok(0x10940));
+        assert_eq!(view.export_rva_by_ordinal(18), Err(PeError::ExportNotFound));
+        assert_eq!(view.export_rva_by_ordinal(1), Err(PeError::ExportNotFound));
+    }
+
+    #[test]
+    fn test_forwarded_exports() {
+        let mut bytes = AlignedBuffer::from(RELOADED_BOOTSTRAPPER_DLL_X64);
+
+        // Write forwarder strings over the DLL name, which is inside the export directory,
+        // and point the first three exports at them.
+        let view = PeView::new(&bytes, false).unwrap();
+        let export_directory = view.export_directory().unwrap();
+        let name_rva = export_directory.name();
+        let name = view.rva_to_offset(name_rva).unwrap();
+        let export_address_table = view.rva_to_offset(export_directory.address_of_functions()).unwrap();
+        bytes[name..name + 33].copy_from_slice(b"NTDLL.RtlAllocateHeap\0api.ms.#12\0");
+        for (x, rva) in [name_rva, name_rva + 22, name_rva + 12].into_iter().enumerate() {
+            bytes[export_address_table + x * 4..export_address_table + x * 4 + 4].copy_from_slice(&rva.to_le_bytes());
+        }
+
+        let pe_start = bytes.as_ptr() as *const c_void;
+        assert_eq!(
+            unsafe { get_export::<AutoDetect>(pe_start, NameOrId::Name("InitializeASI"), false) },
+            Some(ExportTarget::Forwarder {
+                dll: "NTDLL",
+                name_or_ordinal: NameOrId::Name("RtlAllocateHeap")
+            })
+        );
+        assert_eq!(
+            unsafe { get_export::<AutoDetect>(pe_start, NameOrId::Id(2), false) },
+            Some(ExportTarget::Forwarder {
+                dll: "api.ms",
+                name_or_ordinal: NameOrId::Id(12)
+            })
+        );
+        assert_eq!(
+            unsafe { try_get_export::<AutoDetect>(pe_start, NameOrId::Id(3), false) },
+            Err(PeError::MalformedForwarder)
+        );
+        assert_eq!(
+            unsafe { get_export::<AutoDetect>(pe_start, NameOrId::Name("get_hostfxr_path"), false) },
+            Some(ExportTarget::Code(0x10940))
+        );
+    }
 }
@@ -0,0 +1,159 @@
+use crate::{error::PeError, pe_headers::PeHeaders, pe_width::PeWidth};
+use core::ffi::c_void;
+
+/// FNV-1a offset basis, for the 32-bit variant.
+const FNV1A_OFFSET_BASIS: u32 = 0x811C_9DC5;
+
+/// FNV-1a prime, for the 32-bit variant.
+const FNV1A_PRIME: u32 = 0x0100_0193;
+
+/// Hashes an export name with the 32-bit FNV-1a hash.
+///
+/// This is a `const fn`, so the hashes of the exports to look up can be computed at compile time,
+/// e.g. `const INITIALIZE_ASI: u32 = fnv1a(b"InitializeASI");`, rather than embedding the names.
+pub const fn fnv1a(name: &[u8]) -> u32 {
+    let mut hash = FNV1A_OFFSET_BASIS;
+    let mut x = 0;
+    while x < name.len() {
+        hash ^= name[x] as u32;
+        hash = hash.wrapping_mul(FNV1A_PRIME);
+        x += 1;
+    }
+
+    hash
+}
+
+impl<W: PeWidth, const CHECKED: bool> PeHeaders<'_, W, CHECKED> {
+    /// Retrieves the offset of the export whose name has the given hash in Virtual Memory,
+    /// relative to the start of the PE file.
+    ///
+    /// # Arguments
+    /// - `hash` - The hash of the name of the export to search.
+    /// - `hasher` - The function used to compute `hash`, e.g. [`fnv1a`].
+    ///
+    /// # Remarks
+    /// The first export with a matching hash is returned, so the hash should be
+    /// free of collisions for the names exported by the file.
+    ///
+    /// # Returns
+    /// The RVA of the export, [`PeError::ExportNotFound`] if the file has no such export,
+    /// or another [`PeError`] if the export table is malformed.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn export_rva_by_hash<T: PartialEq>(&self, hash: T, hasher: impl Fn(&[u8]) -> T) -> Result<usize, PeError> {
+        let export_directory = self.export_directory()?;
+        let export_name_pointer_table = self.rva_to_offset(export_directory.address_of_names())?;
+
+        for x in 0..export_directory.number_of_names() as usize {
+            // Ignore if the name couldn't be read.
+            let name = match self.export_name(export_name_pointer_table, x) {
+                Ok(name) => name,
+                Err(_) => continue,
+            };
+
+            if hasher(name) == hash {
+                return self.export_rva_by_name_index(&export_directory, x);
+            }
+        }
+
+        // Couldn't find the export
+        Err(PeError::ExportNotFound)
+    }
+}
+
+/// Retrieves the offset of the export whose name has the given hash in Virtual Memory,
+/// relative to the start of the PE file. Add the returned value to the address
+/// of the mapped PE file to get the absolute address of the export.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `hash` - The hash of the name of the export to search.
+/// - `hasher` - The function used to compute `hash`, e.g. [`fnv1a`].
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+///
+/// A value of [`usize::MAX`] indicates that the export was not found.
+/// Otherwise, the return value is the absolute offset of the export in memory.
+///
+/// Use [`try_get_export_rva_by_hash`] to find out why an export could not be found.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_export_rva_by_hash<W: PeWidth, T: PartialEq>(
+    pe_start: *const c_void,
+    hash: T,
+    hasher: impl Fn(&[u8]) -> T,
+    is_mapped: bool,
+) -> usize {
+    try_get_export_rva_by_hash::<W, T>(pe_start, hash, hasher, is_mapped).unwrap_or(usize::MAX)
+}
+
+/// Retrieves the offset of the export whose name has the given hash in Virtual Memory,
+/// relative to the start of the PE file. Add the returned value to the address
+/// of the mapped PE file to get the absolute address of the export.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `hash` - The hash of the name of the export to search.
+/// - `hasher` - The function used to compute `hash`, e.g. [`fnv1a`].
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+///
+/// The absolute offset of the export in memory, [`PeError::ExportNotFound`] if the
+/// file has no such export, or another [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_export_rva_by_hash<W: PeWidth, T: PartialEq>(
+    pe_start: *const c_void,
+    hash: T,
+    hasher: impl Fn(&[u8]) -> T,
+    is_mapped: bool,
+) -> Result<usize, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.export_rva_by_hash(hash, hasher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_width::{AutoDetect, Pe32, Pe64},
+        utils::test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+    };
+
+    const INITIALIZE_ASI: u32 = fnv1a(b"InitializeASI");
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0x811C_9DC5);
+        assert_eq!(fnv1a(b"a"), 0xE40C_292C);
+        assert_eq!(fnv1a(b"foobar"), 0xBF9C_F968);
+    }
+
+    #[test]
+    fn test_get_export_rva_by_hash() {
+        let pe_start = RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void;
+        assert_eq!(
+            unsafe { get_export_rva_by_hash::<Pe64, _>(pe_start, INITIALIZE_ASI, fnv1a, false) },
+            0x236c
+        );
+        assert_eq!(
+            unsafe { get_export_rva_by_hash::<Pe64, _>(pe_start, fnv1a(b"get_hostfxr_path"), fnv1a, false) },
+            0x10940
+        );
+        assert_eq!(
+            unsafe { try_get_export_rva_by_hash::<AutoDetect, _>(pe_start, fnv1a(b"DoesNotExist"), fnv1a, false) },
+            Err(PeError::ExportNotFound)
+        );
+
+        // Any hash function can be used.
+        let pe_start = RELOADED_BOOTSTRAPPER_DLL_X86.as_ptr() as *const c_void;
+        assert_eq!(
+            unsafe { get_export_rva_by_hash::<Pe32, _>(pe_start, 20, |x: &[u8]| x.len(), false) },
+            0xb1b0
+        );
+    }
+}
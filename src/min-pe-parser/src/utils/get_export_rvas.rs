@@ -0,0 +1,187 @@
+use crate::{error::PeError, pe_headers::PeHeaders, pe_width::PeWidth};
+use alloc::vec::Vec;
+use core::ffi::c_void;
+
+impl<W: PeWidth, const CHECKED: bool> PeHeaders<'_, W, CHECKED> {
+    /// Retrieves the offsets of many exports in Virtual Memory, relative to the start
+    /// of the PE file, in a single pass over the export names.
+    ///
+    /// # Arguments
+    /// - `export_names` - The names of the exports to search.
+    /// - `rvas` - Receives the RVA of each export in `export_names`, or [`usize::MAX`]
+    ///   if the export was not found. Must be the same length as `export_names`.
+    ///
+    /// # Returns
+    /// The number of exports found, [`PeError::LengthMismatch`] if `export_names` and `rvas`
+    /// have different lengths, or another [`PeError`] if the export table is malformed.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn export_rvas(&self, export_names: &[&str], rvas: &mut [usize]) -> Result<usize, PeError> {
+        if export_names.len() != rvas.len() {
+            return Err(PeError::LengthMismatch);
+        }
+
+        rvas.fill(usize::MAX);
+
+        // Requested names in sorted order, so each export name is found with a binary search.
+        let mut sorted: Vec<usize> = (0..export_names.len()).collect();
+        sorted.sort_unstable_by_key(|&x| export_names[x].as_bytes());
+
+        let export_directory = self.export_directory()?;
+        let export_name_pointer_table = self.rva_to_offset(export_directory.address_of_names())?;
+
+        let mut found = 0;
+        for x in 0..export_directory.number_of_names() as usize {
+            // Stop early once everything has been found.
+            if found == export_names.len() {
+                break;
+            }
+
+            // Ignore if the name couldn't be read.
+            let name = match self.export_name(export_name_pointer_table, x) {
+                Ok(name) => name,
+                Err(_) => continue,
+            };
+
+            // The same name may be requested more than once.
+            let first = sorted.partition_point(|&i| export_names[i].as_bytes() < name);
+            let requested = sorted[first..]
+                .iter()
+                .take_while(|&&i| export_names[i].as_bytes() == name);
+
+            let mut rva = None;
+            for &index in requested {
+                // A malformed table may list a name twice; the first one wins.
+                if rvas[index] != usize::MAX {
+                    continue;
+                }
+
+                rvas[index] = match rva {
+                    Some(rva) => rva,
+                    None => *rva.insert(self.export_rva_by_name_index(&export_directory, x)?),
+                };
+                found += 1;
+            }
+        }
+
+        Ok(found)
+    }
+}
+
+/// Retrieves the offsets of many exports in Virtual Memory, relative to the start
+/// of the PE file, in a single pass over the export names. Add the returned values
+/// to the address of the mapped PE file to get the absolute addresses of the exports.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `export_names` - The names of the exports to search.
+/// - `rvas` - Receives the RVA of each export in `export_names`, or [`usize::MAX`]
+///   if the export was not found. Must be the same length as `export_names`.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+/// The number of exports found. Use [`try_get_export_rvas`] to find out why exports could not be found.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_export_rvas<W: PeWidth>(
+    pe_start: *const c_void,
+    export_names: &[&str],
+    rvas: &mut [usize],
+    is_mapped: bool,
+) -> usize {
+    try_get_export_rvas::<W>(pe_start, export_names, rvas, is_mapped).unwrap_or_else(|_| {
+        rvas.fill(usize::MAX);
+        0
+    })
+}
+
+/// Retrieves the offsets of many exports in Virtual Memory, relative to the start
+/// of the PE file, in a single pass over the export names. Add the returned values
+/// to the address of the mapped PE file to get the absolute addresses of the exports.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `export_names` - The names of the exports to search.
+/// - `rvas` - Receives the RVA of each export in `export_names`, or [`usize::MAX`]
+///   if the export was not found. Must be the same length as `export_names`.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+/// The number of exports found, [`PeError::LengthMismatch`] if `export_names` and `rvas`
+/// have different lengths, or another [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_export_rvas<W: PeWidth>(
+    pe_start: *const c_void,
+    export_names: &[&str],
+    rvas: &mut [usize],
+    is_mapped: bool,
+) -> Result<usize, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.export_rvas(export_names, rvas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::{AutoDetect, Pe32, Pe64},
+        utils::test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+    };
+
+    #[test]
+    fn test_get_export_rvas_x64() {
+        let pe_start = RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void;
+        let names = [
+            "get_hostfxr_path",
+            "DoesNotExist",
+            "InitializeASI",
+            "SonicRModInfo",
+            "InitializeASI",
+        ];
+        let mut rvas = [0; 5];
+
+        let found = unsafe { get_export_rvas::<Pe64>(pe_start, &names, &mut rvas, false) };
+        assert_eq!(found, 4);
+        assert_eq!(rvas, [0x10940, usize::MAX, 0x236c, 0x240c0, 0x236c]);
+    }
+
+    #[test]
+    fn test_get_export_rvas_x86() {
+        let pe_start = RELOADED_BOOTSTRAPPER_DLL_X86.as_ptr() as *const c_void;
+        let names = ["InitializeASI", "_get_hostfxr_path@12"];
+        let mut rvas = [0; 2];
+
+        let found = unsafe { get_export_rvas::<Pe32>(pe_start, &names, &mut rvas, false) };
+        assert_eq!(found, 2);
+        assert_eq!(rvas, [0x1caa, 0xb1b0]);
+    }
+
+    #[test]
+    fn test_try_get_export_rvas_not_a_pe() {
+        let not_a_pe = [0u32; 64];
+        let pe_start = not_a_pe.as_ptr() as *const c_void;
+        let mut rvas = [0; 1];
+
+        let result = unsafe { try_get_export_rvas::<AutoDetect>(pe_start, &["InitializeASI"], &mut rvas, false) };
+        assert_eq!(result, Err(PeError::InvalidDosMagic));
+        assert_eq!(
+            unsafe { get_export_rvas::<AutoDetect>(pe_start, &["InitializeASI"], &mut rvas, false) },
+            0
+        );
+        assert_eq!(rvas, [usize::MAX]);
+    }
+
+    #[test]
+    fn test_export_rvas_length_mismatch() {
+        let view = PeView::new(RELOADED_BOOTSTRAPPER_DLL_X64, false).unwrap();
+        let mut rvas = [0; 1];
+        assert_eq!(
+            view.export_rvas(&["InitializeASI", "SonicRModInfo"], &mut rvas),
+            Err(PeError::LengthMismatch)
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use crate::{error::PeError, pe_headers::PeHeaders, pe_width::PeWidth, utils::get_export_rva::ExportTarget};
+use alloc::{vec, vec::Vec};
+use core::{ffi::c_void, mem::size_of};
+use simple_endian::LittleEndian;
+
+/// An entry of the export address table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Export<'a> {
+    /// The ordinal of the export, i.e. its index in the export address table plus
+    /// [`IMAGE_EXPORT_DIRECTORY::base`](crate::structs::IMAGE_EXPORT_DIRECTORY::IMAGE_EXPORT_DIRECTORY::base).
+    pub ordinal: u32,
+    /// The name of the export, or [`None`] if it is exported by ordinal only.
+    pub name: Option<&'a str>,
+    /// The RVA in the export address table entry. For a forwarded export,
+    /// this is the RVA of the forwarder string.
+    pub rva: u32,
+    /// What the export refers to, decoded from [`Export::rva`]; code (or data) in the file,
+    /// or an export of another DLL.
+    pub target: ExportTarget<'a>,
+}
+
+/// Iterator over the entries of the export address table, in ordinal order.
+/// Unused (zero) entries are skipped. Forwarded exports with a malformed forwarder string
+/// are listed as [`ExportTarget::MalformedForwarder`].
+///
+/// Created with [`PeHeaders::exports`].
+#[derive(Clone, Debug)]
+pub struct Exports<'a, W: PeWidth, const CHECKED: bool> {
+    headers: PeHeaders<'a, W, CHECKED>,
+    export_address_table: usize,
+    base: u32,
+    index: usize,
+    /// Name of each entry in the export address table.
+    names: Vec<Option<&'a str>>,
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Returns an iterator over all entries in the export address table,
+    /// including those which are exported by ordinal only.
+    ///
+    /// # Remarks
+    /// Names which cannot be read, or are not valid UTF-8, are treated as missing.
+    ///
+    /// # Returns
+    /// A file without an export table returns [`PeError::MissingDirectory`].
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn exports(&self) -> Result<Exports<'a, W, CHECKED>, PeError> {
+        let export_directory = self.export_directory()?;
+        let num_functions = export_directory.number_of_functions() as usize;
+
+        // Ensure the whole export address table is there, before allocating for it.
+        let export_address_table = self.rva_to_offset(export_directory.address_of_functions())?;
+        self.bytes(
+            export_address_table,
+            num_functions.checked_mul(size_of::<u32>()).ok_or(PeError::Truncated)?,
+        )?;
+
+        // Match the names to the export address table entries, via the ordinal table.
+        let mut names = vec![None; num_functions];
+        if export_directory.number_of_names() != 0 {
+            let name_pointer_table = self.rva_to_offset(export_directory.address_of_names())?;
+            let ordinal_table = self.rva_to_offset(export_directory.address_of_name_ordinals())?;
+            for x in 0..export_directory.number_of_names() as usize {
+                let name_rva: LittleEndian<u32> = self.read(name_pointer_table + x * size_of::<u32>())?;
+                let index: LittleEndian<u16> = self.read(ordinal_table + x * size_of::<u16>())?;
+                let name = self.rva_to_offset(name_rva.into()).and_then(|x| self.utf8_str(x));
+                if let (Some(entry), Ok(name)) = (names.get_mut(u16::from(index) as usize), name) {
+                    *entry = Some(name);
+                }
+            }
+        }
+
+        Ok(Exports {
+            headers: *self,
+            export_address_table,
+            base: export_directory.base(),
+            index: 0,
+            names,
+        })
+    }
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> Iterator for Exports<'a, W, CHECKED> {
+    type Item = Export<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.names.len() {
+            let index = self.index;
+            self.index += 1;
+
+            // In bounds, this was checked on creation.
+            let rva: LittleEndian<u32> = self
+                .headers
+                .read(self.export_address_table + index * size_of::<u32>())
+                .ok()?;
+
+            let rva = u32::from(rva);
+            if rva == 0 {
+                continue;
+            }
+
+            let target = self
+                .headers
+                .export_target(rva)
+                .unwrap_or(ExportTarget::MalformedForwarder(rva));
+
+            return Some(Export {
+                ordinal: self.base.wrapping_add(index as u32),
+                name: self.names[index],
+                rva,
+                target,
+            });
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.names.len() - self.index))
+    }
+}
+
+/// Get all entries of the export address table, including those which are exported by ordinal only.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Remarks
+/// This function assumes that the PE file is valid and that the pointer is not null.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// All exports, or an empty [`Vec`] if they could not be read.
+/// Use [`try_get_exports`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_exports<'a, W: PeWidth>(pe_start: *const c_void, is_mapped: bool) -> Vec<Export<'a>> {
+    try_get_exports::<W>(pe_start, is_mapped).map_or_else(|_| Vec::new(), |x| x.collect())
+}
+
+/// Returns an iterator over all entries of the export address table,
+/// including those which are exported by ordinal only.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The iterator. A file without an export table returns [`PeError::MissingDirectory`].
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_exports<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<Exports<'a, W, false>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.exports()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        name_or_id::NameOrId,
+        pe_view::PeView,
+        pe_width::{AutoDetect, Pe32},
+        prelude::*,
+        utils::{
+            aligned_buffer::AlignedBuffer,
+            test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+        },
+    };
+
+    #[test]
+    fn test_reloaded_bootstrapper_dll_exports_x64() {
+        let exports =
+            unsafe { get_exports::<AutoDetect>(RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void, false) };
+
+        assert_eq!(exports.len(), 8);
+        assert_eq!(
+            exports[0],
+            Export {
+                ordinal: 1,
+                name: Some("InitializeASI"),
+                rva: 0x236c,
+                target: ExportTarget::Code(0x236c)
+            }
+        );
+        assert_eq!(
+            exports[7],
+            Export {
+                ordinal: 8,
+                name: Some("get_hostfxr_path"),
+                rva: 0x10940,
+                target: ExportTarget::Code(0x10940)
+            }
+        );
+    }
+
+    #[test]
+    fn test_reloaded_bootstrapper_dll_exports_x86() {
+        let exports = unsafe { get_exports::<Pe32>(RELOADED_BOOTSTRAPPER_DLL_X86.as_ptr() as *const c_void, false) };
+
+        assert_eq!(exports.len(), 8);
+        assert_eq!(exports[7].name, Some("_get_hostfxr_path@12"));
+        assert_eq!(exports[7].target, ExportTarget::Code(0xb1b0));
+    }
+
+    #[test]
+    fn test_exports_ordinal_only_and_unused_entries() {
+        let mut bytes = AlignedBuffer::from(RELOADED_BOOTSTRAPPER_DLL_X64);
+
+        let view = PeView::new(&bytes, false).unwrap();
+        let (directory, _) = view.data_directory_offset(DataDirectoryType::ExportTable).unwrap();
+        let export_address_table = view
+            .rva_to_offset(view.export_directory().unwrap().address_of_functions())
+            .unwrap();
+
+        // Ordinal base 10, drop the name of the last export, and leave the second entry unused.
+        let mut export_directory = view.export_directory().unwrap();
+        export_directory.set_base(10);
+        export_directory.set_number_of_names(7);
+        unsafe { (bytes.as_mut_ptr().add(directory) as *mut IMAGE_EXPORT_DIRECTORY).write_unaligned(export_directory) };
+        bytes[export_address_table + 4..export_address_table + 8].fill(0);
+
+        let exports: Vec<_> = PeView::new(&bytes, false).unwrap().exports().unwrap().collect();
+        assert_eq!(exports.len(), 7);
+        assert_eq!(exports[0].ordinal, 10);
+        assert_eq!(exports[1].ordinal, 12);
+        assert_eq!(exports[1].name, Some("ManiaModInfo"));
+        assert_eq!(
+            exports[6],
+            Export {
+                ordinal: 17,
+                name: None,
+                rva: 0x10940,
+                target: ExportTarget::Code(0x10940)
+            }
+        );
+    }
+
+    #[test]
+    fn test_exports_forwarders() {
+        let mut image = AlignedBuffer::from(RELOADED_BOOTSTRAPPER_DLL_X64);
+        let view = PeView::new(&image, false).unwrap();
+        let export_directory = view.export_directory().unwrap();
+        let export_address_table = view.rva_to_offset(export_directory.address_of_functions()).unwrap();
+        let name_pointer_table = view.rva_to_offset(export_directory.address_of_names()).unwrap();
+
+        // Point the first entry at the name of an export; within the export directory,
+        // so a forwarder, but without a `.` separating the DLL from the function.
+        let name: [u8; 4] = image[name_pointer_table..name_pointer_table + 4].try_into().unwrap();
+        image[export_address_table..export_address_table + 4].copy_from_slice(&name);
+
+        // Point the second entry at the DLL's own name, `Reloaded.Mod.Loader.Bootstrapper`.
+        let dll_name = export_directory.name();
+        image[export_address_table + 4..export_address_table + 8].copy_from_slice(&dll_name.to_le_bytes());
+
+        let view = PeView::new(&image, false).unwrap();
+        let exports: Vec<_> = view.exports().unwrap().collect();
+        assert_eq!(exports.len(), 8);
+        assert_eq!(exports[0].rva, u32::from_le_bytes(name));
+        assert_eq!(
+            exports[0].target,
+            ExportTarget::MalformedForwarder(u32::from_le_bytes(name))
+        );
+        assert_eq!(
+            view.export_target(u32::from_le_bytes(name)),
+            Err(PeError::MalformedForwarder)
+        );
+
+        // Forwarded exports keep the RVA of their forwarder string.
+        assert_eq!(exports[1].rva, dll_name);
+        assert_eq!(
+            exports[1].target,
+            ExportTarget::Forwarder {
+                dll: "Reloaded.Mod.Loader",
+                name_or_ordinal: NameOrId::Name("Bootstrapper")
+            }
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{error::PeError, name_or_id::NameOrId, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*};
+use alloc::vec::Vec;
+use core::ffi::c_void;
+
+/// Size of the `ICONDIR` / `GRPICONDIR` header.
+const ICON_DIR_SIZE: usize = 6;
+
+/// Size of a `GRPICONDIRENTRY`; an `ICONDIRENTRY` with a 2 byte resource ID instead of a 4 byte file offset.
+const GROUP_ICON_ENTRY_SIZE: usize = 14;
+
+/// Size of an `ICONDIRENTRY`, in an `.ico` file.
+const ICON_ENTRY_SIZE: usize = 16;
+
+impl<W: PeWidth, const CHECKED: bool> PeHeaders<'_, W, CHECKED> {
+    /// Extracts an icon group (`RT_GROUP_ICON`) and the images (`RT_ICON`) it references,
+    /// as a standalone `.ico` file.
+    ///
+    /// # Arguments
+    /// - `group` - The name or ID of the icon group, or [`None`] for the first one,
+    ///   which is the icon Explorer shows for the file.
+    /// - `language` - The language ID of the icon group, or [`None`] for any language.
+    ///
+    /// # Remarks
+    /// Images are looked up in the language of the group first, then in any language.
+    ///
+    /// # Returns
+    /// The `.ico` file, [`PeError::ResourceNotFound`] if there is no such group, or
+    /// [`PeError::MalformedResource`] if the group is truncated or references a missing image.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn icon(&self, group: Option<NameOrId<&str>>, language: Option<u16>) -> Result<Vec<u8>, PeError> {
+        let group = self.resource(ResourceType::GROUP_ICON.into(), group, language)?;
+        let header = group.data.get(..ICON_DIR_SIZE).ok_or(PeError::MalformedResource)?;
+        let count = u16::from_le_bytes([header[4], header[5]]) as usize;
+        let entries = group
+            .data
+            .get(ICON_DIR_SIZE..ICON_DIR_SIZE + count * GROUP_ICON_ENTRY_SIZE)
+            .ok_or(PeError::MalformedResource)?;
+
+        let mut images = Vec::with_capacity(count);
+        for entry in entries.chunks_exact(GROUP_ICON_ENTRY_SIZE) {
+            let id = NameOrId::Id(u16::from_le_bytes([entry[12], entry[13]]));
+            let image = self
+                .resource(ResourceType::ICON.into(), Some(id), Some(group.language))
+                .or_else(|_| self.resource(ResourceType::ICON.into(), Some(id), None))
+                .map_err(|_| PeError::MalformedResource)?;
+
+            images.push(image.data);
+        }
+
+        let mut offset = ICON_DIR_SIZE + count * ICON_ENTRY_SIZE;
+        let mut ico = Vec::with_capacity(offset + images.iter().map(|x| x.len()).sum::<usize>());
+        ico.extend_from_slice(header);
+
+        // Same entry, but the size is that of the image we actually have, followed by where it is in the file.
+        for (entry, image) in entries.chunks_exact(GROUP_ICON_ENTRY_SIZE).zip(&images) {
+            ico.extend_from_slice(&entry[..8]);
+            ico.extend_from_slice(&(image.len() as u32).to_le_bytes());
+            ico.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += image.len();
+        }
+
+        images.iter().for_each(|x| ico.extend_from_slice(x));
+        Ok(ico)
+    }
+}
+
+/// Extracts an icon group (`RT_GROUP_ICON`) and the images (`RT_ICON`) it references,
+/// as a standalone `.ico` file.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `group` - The name or ID of the icon group, or [`None`] for the first one.
+/// - `language` - The language ID of the icon group, or [`None`] for any language.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+/// The `.ico` file, or [`None`] if it could not be extracted. Use [`try_get_icon`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_icon<W: PeWidth>(
+    pe_start: *const c_void,
+    group: Option<NameOrId<&str>>,
+    language: Option<u16>,
+    is_mapped: bool,
+) -> Option<Vec<u8>> {
+    try_get_icon::<W>(pe_start, group, language, is_mapped).ok()
+}
+
+/// Extracts an icon group (`RT_GROUP_ICON`) and the images (`RT_ICON`) it references,
+/// as a standalone `.ico` file.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `group` - The name or ID of the icon group, or [`None`] for the first one.
+/// - `language` - The language ID of the icon group, or [`None`] for any language.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+/// The `.ico` file, [`PeError::ResourceNotFound`] if there is no such group, or another
+/// [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_icon<W: PeWidth>(
+    pe_start: *const c_void,
+    group: Option<NameOrId<&str>>,
+    language: Option<u16>,
+    is_mapped: bool,
+) -> Result<Vec<u8>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.icon(group, language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::AutoDetect,
+        utils::{
+            map_image::map_image,
+            test_utils::{add_resources, RELOADED_BOOTSTRAPPER_DLL_X64},
+        },
+    };
+
+    /// A `GRPICONDIR` with an entry for each (width, bit count, size, ID).
+    fn group(entries: &[(u8, u16, u32, u16)]) -> Vec<u8> {
+        let mut group = [0u16, 1, entries.len() as u16].map(u16::to_le_bytes).concat();
+        for &(width, bit_count, size, id) in entries {
+            group.extend_from_slice(&[width, width, 0, 0, 1, 0]);
+            group.extend_from_slice(&bit_count.to_le_bytes());
+            group.extend_from_slice(&size.to_le_bytes());
+            group.extend_from_slice(&id.to_le_bytes());
+        }
+
+        group
+    }
+
+    #[test]
+    fn test_icon() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let main = group(&[(16, 32, 4, 1), (0, 32, 8, 2)]);
+        let german = group(&[(32, 8, 3, 3)]);
+        add_resources(
+            &mut image,
+            &[
+                (ResourceType::ICON.into(), NameOrId::Id(1), 0x409, b"ico1"),
+                (ResourceType::ICON.into(), NameOrId::Id(2), 0x409, b"\x89PNG\r\n\x1a\n"),
+                (ResourceType::ICON.into(), NameOrId::Id(3), 0x409, b"en3"),
+                (ResourceType::ICON.into(), NameOrId::Id(3), 0x407, b"de3"),
+                (ResourceType::GROUP_ICON.into(), NameOrId::Id(101), 0x409, &main),
+                (ResourceType::GROUP_ICON.into(), NameOrId::Id(102), 0x407, &german),
+            ],
+        );
+
+        let ico = unsafe { get_icon::<AutoDetect>(image.as_ptr() as *const c_void, None, None, true) }.unwrap();
+        assert_eq!(ico.len(), 6 + 2 * 16 + 4 + 8);
+        assert_eq!(ico[..6], [0, 0, 1, 0, 2, 0]);
+        assert_eq!(ico[6..22], [16, 16, 0, 0, 1, 0, 32, 0, 4, 0, 0, 0, 38, 0, 0, 0]);
+        assert_eq!(ico[22..38], [0, 0, 0, 0, 1, 0, 32, 0, 8, 0, 0, 0, 42, 0, 0, 0]);
+        assert_eq!(&ico[38..], b"ico1\x89PNG\r\n\x1a\n");
+
+        // Images come from the language of the group.
+        let view = PeView::new(&image, true).unwrap();
+        let ico = view.icon(Some(NameOrId::Id(102)), Some(0x407)).unwrap();
+        assert_eq!(ico[6..22], [32, 32, 0, 0, 1, 0, 8, 0, 3, 0, 0, 0, 22, 0, 0, 0]);
+        assert_eq!(&ico[22..], b"de3");
+
+        assert_eq!(
+            view.icon(Some(NameOrId::Id(102)), Some(0x409)),
+            Err(PeError::ResourceNotFound)
+        );
+        assert_eq!(
+            PeView::new(RELOADED_BOOTSTRAPPER_DLL_X64, false)
+                .unwrap()
+                .icon(None, None),
+            Err(PeError::ResourceNotFound)
+        );
+    }
+
+    #[test]
+    fn test_icon_malformed() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+
+        // References an image which does not exist.
+        let missing = group(&[(16, 32, 4, 9)]);
+        add_resources(
+            &mut image,
+            &[(ResourceType::GROUP_ICON.into(), NameOrId::Id(1), 0, &missing)],
+        );
+        assert_eq!(
+            PeView::new(&image, true).unwrap().icon(None, None),
+            Err(PeError::MalformedResource)
+        );
+
+        // More entries than there is data for.
+        let truncated = &group(&[(16, 32, 4, 1)])[..12];
+        add_resources(
+            &mut image,
+            &[
+                (ResourceType::ICON.into(), NameOrId::Id(1), 0, b"ico1"),
+                (ResourceType::GROUP_ICON.into(), NameOrId::Id(1), 0, truncated),
+            ],
+        );
+        assert_eq!(
+            PeView::new(&image, true).unwrap().icon(None, None),
+            Err(PeError::MalformedResource)
+        );
+    }
+}
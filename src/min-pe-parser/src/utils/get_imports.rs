@@ -0,0 +1,315 @@
+use crate::{error::PeError, name_or_id::NameOrId, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*};
+use alloc::vec::Vec;
+use core::{ffi::c_void, mem::size_of};
+use simple_endian::LittleEndian;
+
+/// How a function is imported: by name (with its hint), or by ordinal.
+pub type ImportName<'a> = NameOrId<HintName<'a>>;
+
+/// The hint and name of a function imported by name.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HintName<'a> {
+    /// Index into the exporting DLL's name table, tried first when resolving the import.
+    pub hint: u16,
+    /// Name of the function.
+    pub name: &'a str,
+}
+
+impl ImportName<'_> {
+    /// Checks if this is the given function.
+    pub fn matches(&self, function: NameOrId<&str>) -> bool {
+        match (*self, function) {
+            (NameOrId::Name(import), NameOrId::Name(x)) => import.name == x,
+            (NameOrId::Id(ordinal), NameOrId::Id(x)) => ordinal == x,
+            _ => false,
+        }
+    }
+}
+
+/// A function imported by the PE file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ImportedFunction<'a> {
+    /// The name or ordinal of the function.
+    pub name: ImportName<'a>,
+    /// RVA of the function's slot in the import address table (IAT).
+    pub iat_rva: u32,
+}
+
+/// A DLL imported by the PE file, and the functions imported from it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportedDll<'a> {
+    /// Name of the DLL.
+    pub name: &'a str,
+    /// Functions imported from the DLL, in import table order.
+    pub functions: Vec<ImportedFunction<'a>>,
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Get the DLLs imported by the PE file, along with the functions imported from each.
+    ///
+    /// # Remarks
+    /// The functions are read from the import lookup table (`original_first_thunk`).
+    /// If a DLL has no lookup table, the import address table (`first_thunk`) is read instead;
+    /// this only works if the imports have not yet been resolved (or bound).
+    ///
+    /// # Returns
+    /// All DLLs in the import table, or an empty [`Vec`] if the file has no import table.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn imports(&self) -> Result<Vec<ImportedDll<'a>>, PeError> {
+        self.read_imports(false)
+    }
+
+    /// Like [`PeHeaders::imports`], but for an image whose imports have been resolved.
+    /// DLLs without an import lookup table are skipped, as their IAT now holds addresses.
+    pub(crate) fn resolved_imports(&self) -> Result<Vec<ImportedDll<'a>>, PeError> {
+        self.read_imports(true)
+    }
+
+    fn read_imports(&self, is_resolved: bool) -> Result<Vec<ImportedDll<'a>>, PeError> {
+        // Determine the start and end of the import descriptors array.
+        let Some((import_descriptors, size)) = self.data_directory_offset_if_present(DataDirectoryType::ImportTable)?
+        else {
+            return Ok(Vec::new());
+        };
+        let num_import_descriptors = size as usize / size_of::<IMAGE_IMPORT_DESCRIPTOR>();
+
+        let mut dlls = Vec::with_capacity(if CHECKED { 0 } else { num_import_descriptors });
+        for x in 0..num_import_descriptors {
+            let descriptor: IMAGE_IMPORT_DESCRIPTOR =
+                self.read(import_descriptors + x * size_of::<IMAGE_IMPORT_DESCRIPTOR>())?;
+
+            // A null descriptor terminates the table.
+            if descriptor.name() == 0 {
+                break;
+            }
+
+            let lookup_rva = match descriptor.original_first_thunk() {
+                0 if is_resolved => continue,
+                0 => descriptor.first_thunk(),
+                rva => rva,
+            };
+
+            dlls.push(ImportedDll {
+                name: self.utf8_str(self.rva_to_offset(descriptor.name())?)?,
+                functions: self.imported_functions(lookup_rva, descriptor.first_thunk())?,
+            });
+        }
+
+        Ok(dlls)
+    }
+
+    /// Reads the null terminated thunk array at `lookup_rva`, pairing each thunk with its IAT slot.
+    fn imported_functions(&self, lookup_rva: u32, iat_rva: u32) -> Result<Vec<ImportedFunction<'a>>, PeError> {
+        let lookup_table = self.rva_to_offset(lookup_rva)?;
+        let thunk_size = self.thunk_size();
+
+        let mut functions = Vec::new();
+        for x in 0.. {
+            // A null thunk terminates the table.
+            let name = match self.lookup_thunk(lookup_table + x * thunk_size)? {
+                None => break,
+                Some(NameOrId::Id(ordinal)) => NameOrId::Id(ordinal),
+                Some(NameOrId::Name(hint_name_rva)) => self.import_by_name(hint_name_rva)?,
+            };
+
+            functions.push(ImportedFunction {
+                name,
+                iat_rva: iat_rva.wrapping_add((x * thunk_size) as u32),
+            });
+        }
+
+        Ok(functions)
+    }
+
+    /// Reads the hint and name from the [`IMAGE_IMPORT_BY_NAME`] at the given RVA.
+    pub(crate) fn import_by_name(&self, hint_name_rva: u32) -> Result<ImportName<'a>, PeError> {
+        let hint_name = self.rva_to_offset(hint_name_rva)?;
+        Ok(NameOrId::Name(HintName {
+            hint: self.read::<LittleEndian<u16>>(hint_name)?.into(),
+            name: self.utf8_str(hint_name + size_of::<u16>())?,
+        }))
+    }
+}
+
+/// Get the DLLs imported by the PE file, along with the functions imported from each.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Remarks
+/// This function assumes that the PE file is valid and that the pointer is not null.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// All DLLs in the import table, or an empty [`Vec`] if they could not be read.
+/// Use [`try_get_imports`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_imports<'a, W: PeWidth>(pe_start: *const c_void, is_mapped: bool) -> Vec<ImportedDll<'a>> {
+    try_get_imports::<W>(pe_start, is_mapped).unwrap_or_default()
+}
+
+/// Get the DLLs imported by the PE file, along with the functions imported from each.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// All DLLs in the import table, or an empty [`Vec`] if the file has no import table.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_imports<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<Vec<ImportedDll<'a>>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.imports()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::{AutoDetect, Pe32, Pe64},
+        utils::{
+            aligned_buffer::AlignedBuffer,
+            test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+        },
+    };
+    use alloc::vec;
+
+    #[test]
+    fn test_reloaded_bootstrapper_dll_imported_functions_x64() {
+        let pe_start = RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void;
+        let imports = unsafe { get_imports::<Pe64>(pe_start, false) };
+
+        assert_eq!(imports.len(), 16);
+        assert_eq!(imports[0].name, "KERNEL32.dll");
+        assert_eq!(imports[0].functions.len(), 41);
+        assert_eq!(
+            imports[0].functions[1],
+            ImportedFunction {
+                name: NameOrId::Name(HintName {
+                    hint: 497,
+                    name: "GetCommandLineW"
+                }),
+                iat_rva: 0x1b028,
+            }
+        );
+        assert_eq!(
+            imports[1],
+            ImportedDll {
+                name: "USER32.dll",
+                functions: vec![ImportedFunction {
+                    name: NameOrId::Name(HintName {
+                        hint: 644,
+                        name: "MessageBoxA"
+                    }),
+                    iat_rva: 0x1b3e8,
+                }],
+            }
+        );
+        assert_eq!(imports.iter().map(|x| x.functions.len()).sum::<usize>(), 181);
+    }
+
+    #[test]
+    fn test_reloaded_bootstrapper_dll_imported_functions_x86() {
+        let pe_start = RELOADED_BOOTSTRAPPER_DLL_X86.as_ptr() as *const c_void;
+        let imports = unsafe { get_imports::<Pe32>(pe_start, false) };
+
+        assert_eq!(imports.len(), 15);
+        assert_eq!(imports[0].functions.len(), 37);
+        assert_eq!(
+            imports[0].functions[1],
+            ImportedFunction {
+                name: NameOrId::Name(HintName {
+                    hint: 491,
+                    name: "GetCommandLineW"
+                }),
+                iat_rva: 0x15014,
+            }
+        );
+        assert_eq!(imports[1].functions[0].iat_rva, 0x151dc);
+        assert_eq!(imports.iter().map(|x| x.functions.len()).sum::<usize>(), 175);
+    }
+
+    #[test]
+    fn test_imported_functions_by_ordinal_and_without_lookup_table() {
+        let mut bytes = AlignedBuffer::from(RELOADED_BOOTSTRAPPER_DLL_X64);
+
+        let view = PeView::new(&bytes, false).unwrap();
+        let (descriptor, _) = view.data_directory_offset(DataDirectoryType::ImportTable).unwrap();
+        let kernel32: IMAGE_IMPORT_DESCRIPTOR = view.read(descriptor).unwrap();
+        let lookup_table = view.rva_to_offset(kernel32.original_first_thunk()).unwrap();
+
+        // Import KERNEL32's second function by ordinal 0x1234.
+        bytes[lookup_table + 8..lookup_table + 16].copy_from_slice(&(IMAGE_ORDINAL_FLAG64 | 0x1234).to_le_bytes());
+        let imports = PeView::new(&bytes, false).unwrap().imports().unwrap();
+        assert_eq!(imports[0].functions[1].name, NameOrId::Id(0x1234));
+        assert_eq!(imports[0].functions[1].iat_rva, kernel32.first_thunk() + 8);
+
+        // Without original_first_thunk, the (unbound) IAT is read instead.
+        bytes[descriptor..descriptor + 4].fill(0);
+        let imports = PeView::new(&bytes, false).unwrap().imports().unwrap();
+        assert_eq!(imports[0].functions.len(), 41);
+        assert_eq!(imports[0].functions[0].iat_rva, kernel32.first_thunk());
+    }
+
+    #[test]
+    fn test_imports_without_import_table() {
+        let mut bytes = AlignedBuffer::from(RELOADED_BOOTSTRAPPER_DLL_X64);
+        let view = PeView::new(&bytes, false).unwrap();
+        let directory = view.data_directories()[DataDirectoryType::ImportTable as usize..].as_ptr() as usize
+            - view.pe_start() as usize;
+
+        bytes[directory..directory + 8].fill(0);
+        let view = PeView::new(&bytes, false).unwrap();
+        assert_eq!(view.imports(), Ok(Vec::new()));
+        assert_eq!(view.resolved_imports(), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_thunk_decoding() {
+        assert_eq!(
+            IMAGE_THUNK_DATA32::new(IMAGE_ORDINAL_FLAG32 | 7).decode(false),
+            Thunk::Ordinal(7)
+        );
+        assert_eq!(
+            IMAGE_THUNK_DATA32::new(0x1a6de).decode(false),
+            Thunk::ByName { hint_name_rva: 0x1a6de }
+        );
+        assert_eq!(
+            IMAGE_THUNK_DATA64::new(IMAGE_ORDINAL_FLAG64 | 7).decode(false),
+            Thunk::Ordinal(7)
+        );
+        // The PE32 ordinal flag is just a high RVA in PE32+.
+        assert_eq!(
+            IMAGE_THUNK_DATA64::new(IMAGE_ORDINAL_FLAG32 as u64).decode(false),
+            Thunk::ByName {
+                hint_name_rva: IMAGE_ORDINAL_FLAG32
+            }
+        );
+        assert_eq!(
+            IMAGE_THUNK_DATA64::new(0x7ffa_1234_5678).decode(true),
+            Thunk::Bound(0x7ffa_1234_5678)
+        );
+        assert!(IMAGE_THUNK_DATA64::new(IMAGE_ORDINAL_FLAG64).is_ordinal());
+    }
+
+    #[test]
+    fn test_try_get_imports_not_a_pe() {
+        let not_a_pe = [0u32; 64];
+        let pe_start = not_a_pe.as_ptr() as *const c_void;
+
+        let result = unsafe { try_get_imports::<AutoDetect>(pe_start, false) };
+        assert_eq!(result, Err(PeError::InvalidDosMagic));
+        assert!(unsafe { get_imports::<AutoDetect>(pe_start, false) }.is_empty());
+    }
+}
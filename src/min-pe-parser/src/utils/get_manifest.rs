@@ -0,0 +1,417 @@
+use crate::{error::PeError, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*};
+use alloc::vec::Vec;
+use core::ffi::c_void;
+
+/// An `assemblyIdentity` element, naming either the application itself or a side-by-side dependency.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AssemblyIdentity<'a> {
+    /// The `name` attribute, e.g. `Microsoft.Windows.Common-Controls`.
+    pub name: Option<&'a str>,
+    /// The `version` attribute, e.g. `6.0.0.0`.
+    pub version: Option<&'a str>,
+    /// The `type` attribute, usually `win32`.
+    pub ty: Option<&'a str>,
+    /// The `processorArchitecture` attribute, e.g. `amd64` or `*`.
+    pub processor_architecture: Option<&'a str>,
+    /// The `publicKeyToken` attribute.
+    pub public_key_token: Option<&'a str>,
+    /// The `language` attribute, e.g. `*`.
+    pub language: Option<&'a str>,
+}
+
+/// The `requestedExecutionLevel` element, from the `trustInfo` section.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionLevel<'a> {
+    /// The `level` attribute; `asInvoker`, `highestAvailable` or `requireAdministrator`.
+    pub level: &'a str,
+    /// The `uiAccess` attribute, if present.
+    pub ui_access: Option<bool>,
+}
+
+/// An application manifest (`RT_MANIFEST`).
+///
+/// Attribute values and text are borrowed from the manifest as is;
+/// XML entities such as `&amp;` are not decoded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest<'a> {
+    /// The manifest, as text.
+    pub text: &'a str,
+    /// The identity of the application (or DLL) itself.
+    pub identity: Option<AssemblyIdentity<'a>>,
+    /// The side-by-side assemblies the application depends on, e.g. Common Controls 6.
+    pub dependencies: Vec<AssemblyIdentity<'a>>,
+    /// The requested execution level, if any.
+    pub execution_level: Option<ExecutionLevel<'a>>,
+    /// The contents of the `dpiAware` element, e.g. `true/pm`.
+    pub dpi_aware: Option<&'a str>,
+    /// The contents of the `dpiAwareness` element, e.g. `PerMonitorV2, PerMonitor`.
+    pub dpi_awareness: Option<&'a str>,
+}
+
+impl<'a> Manifest<'a> {
+    /// Parses a manifest from its text.
+    ///
+    /// # Remarks
+    /// This is not a validating XML parser; it only understands enough of XML to find
+    /// the elements above. Namespace prefixes on element names are ignored.
+    ///
+    /// # Returns
+    /// The manifest, or [`PeError::MalformedResource`] if a tag is not terminated.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn parse(text: &'a str) -> Result<Self, PeError> {
+        let mut manifest = Manifest {
+            text,
+            ..Default::default()
+        };
+
+        let mut path: Vec<&str> = Vec::new();
+        let mut tokens = XmlTokens { rest: text };
+        while let Some(token) = tokens.next_token()? {
+            match token {
+                XmlToken::Start {
+                    name,
+                    attributes,
+                    self_closing,
+                } => {
+                    let name = local_name(name);
+                    match (name, path.last().copied()) {
+                        ("assemblyIdentity", Some("assembly")) => {
+                            manifest.identity = Some(AssemblyIdentity::parse(attributes));
+                        }
+                        ("assemblyIdentity", Some("dependentAssembly")) => {
+                            manifest.dependencies.push(AssemblyIdentity::parse(attributes));
+                        }
+                        ("requestedExecutionLevel", _) => {
+                            manifest.execution_level = attribute(attributes, "level").map(|level| ExecutionLevel {
+                                level,
+                                ui_access: attribute(attributes, "uiAccess").map(|x| x.eq_ignore_ascii_case("true")),
+                            });
+                        }
+                        _ => {}
+                    }
+
+                    if !self_closing {
+                        path.push(name);
+                    }
+                }
+                XmlToken::End => {
+                    path.pop();
+                }
+                XmlToken::Text(text) => match path.last().copied() {
+                    Some("dpiAware") => manifest.dpi_aware = Some(text.trim()),
+                    Some("dpiAwareness") => manifest.dpi_awareness = Some(text.trim()),
+                    _ => {}
+                },
+            }
+        }
+
+        Ok(manifest)
+    }
+}
+
+impl<'a> AssemblyIdentity<'a> {
+    /// Reads the identity from the attributes of an `assemblyIdentity` element.
+    fn parse(attributes: &'a str) -> Self {
+        AssemblyIdentity {
+            name: attribute(attributes, "name"),
+            version: attribute(attributes, "version"),
+            ty: attribute(attributes, "type"),
+            processor_architecture: attribute(attributes, "processorArchitecture"),
+            public_key_token: attribute(attributes, "publicKeyToken"),
+            language: attribute(attributes, "language"),
+        }
+    }
+}
+
+/// A piece of an XML document.
+enum XmlToken<'a> {
+    /// A start tag, or an empty element tag if `self_closing`.
+    Start {
+        name: &'a str,
+        attributes: &'a str,
+        self_closing: bool,
+    },
+    /// An end tag.
+    End,
+    /// Text between tags.
+    Text(&'a str),
+}
+
+/// Splits an XML document into tags and text. Declarations, comments,
+/// processing instructions and CDATA sections are skipped.
+struct XmlTokens<'a> {
+    rest: &'a str,
+}
+
+impl<'a> XmlTokens<'a> {
+    fn next_token(&mut self) -> Result<Option<XmlToken<'a>>, PeError> {
+        loop {
+            if self.rest.is_empty() {
+                return Ok(None);
+            }
+
+            // Text, up to the next tag.
+            if !self.rest.starts_with('<') {
+                let end = self.rest.find('<').unwrap_or(self.rest.len());
+                let (text, rest) = self.rest.split_at(end);
+                self.rest = rest;
+                return Ok(Some(XmlToken::Text(text)));
+            }
+
+            let skipped = [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>"), ("<!", ">")]
+                .into_iter()
+                .find(|(start, _)| self.rest.starts_with(start));
+
+            if let Some((_, end)) = skipped {
+                let len = self.rest.find(end).ok_or(PeError::MalformedResource)?;
+                self.rest = &self.rest[len + end.len()..];
+                continue;
+            }
+
+            let end = tag_end(self.rest).ok_or(PeError::MalformedResource)?;
+            let tag = &self.rest[1..end];
+            self.rest = &self.rest[end + 1..];
+
+            if tag.starts_with('/') {
+                return Ok(Some(XmlToken::End));
+            }
+
+            let (tag, self_closing) = match tag.strip_suffix('/') {
+                Some(tag) => (tag, true),
+                None => (tag, false),
+            };
+
+            let name_end = tag.find(|x: char| x.is_ascii_whitespace()).unwrap_or(tag.len());
+            return Ok(Some(XmlToken::Start {
+                name: &tag[..name_end],
+                attributes: &tag[name_end..],
+                self_closing,
+            }));
+        }
+    }
+}
+
+/// Finds the `>` closing the tag at the start of `text`, skipping over quoted attribute values.
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, x) in text.bytes().enumerate() {
+        match (quote, x) {
+            (None, b'>') => return Some(index),
+            (None, b'"' | b'\'') => quote = Some(x),
+            (Some(q), _) if q == x => quote = None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Strips the namespace prefix from an element name, e.g. `asmv3:application` → `application`.
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Finds the value of the attribute `name` in the attributes of a start tag.
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attributes;
+    loop {
+        let (key, after) = rest.split_once('=')?;
+        let after = after.trim_start();
+        let quote = after.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+
+        let (value, after) = after[1..].split_once(quote)?;
+        if key.trim() == name {
+            return Some(value);
+        }
+
+        rest = after;
+    }
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Finds and parses the application manifest (`RT_MANIFEST`).
+    ///
+    /// # Arguments
+    /// - `language` - The language ID of the resource, or [`None`] for any language.
+    ///
+    /// # Remarks
+    /// Executables store their manifest under ID 1, DLLs under ID 2. The first manifest found is used.
+    ///
+    /// Only UTF-8 manifests (with or without a byte order mark) are supported, as the result
+    /// borrows from the file. The loader also accepts UTF-16 manifests, but toolchains do not
+    /// produce them; these return [`PeError::MalformedResource`].
+    ///
+    /// # Returns
+    /// The manifest, [`PeError::ResourceNotFound`] if there is no manifest, or
+    /// [`PeError::MalformedResource`] if it is not UTF-8 or could not be parsed.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn manifest(&self, language: Option<u16>) -> Result<Manifest<'a>, PeError> {
+        let resource = self.resource(ResourceType::MANIFEST.into(), None, language)?;
+
+        // Allow for a byte order mark, and a null terminator.
+        let data = resource.data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(resource.data);
+        let data = &data[..data.iter().rposition(|&x| x != 0).map_or(0, |x| x + 1)];
+        let text = core::str::from_utf8(data).map_err(|_| PeError::MalformedResource)?;
+        Manifest::parse(text)
+    }
+}
+
+/// Finds and parses the application manifest (`RT_MANIFEST`).
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The manifest, or [`None`] if it could not be read. Use [`try_get_manifest`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_manifest<'a, W: PeWidth>(pe_start: *const c_void, is_mapped: bool) -> Option<Manifest<'a>> {
+    try_get_manifest::<W>(pe_start, is_mapped).ok()
+}
+
+/// Finds and parses the application manifest (`RT_MANIFEST`).
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The manifest, [`PeError::ResourceNotFound`] if there is no manifest,
+/// or another [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_manifest<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<Manifest<'a>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.manifest(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::AutoDetect,
+        utils::{map_image::map_image, test_utils::RELOADED_BOOTSTRAPPER_DLL_X64},
+    };
+
+    const GAME_MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<!-- Generated by the linker -->
+<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+  <assemblyIdentity version="1.2.3.4" processorArchitecture="amd64" name="Sega.SonicHeroes" type="win32"/>
+  <dependency>
+    <dependentAssembly>
+      <assemblyIdentity type="win32" name="Microsoft.Windows.Common-Controls" version="6.0.0.0"
+        processorArchitecture="*" publicKeyToken="6595b64144ccf1df" language="*" />
+    </dependentAssembly>
+  </dependency>
+  <ms_asmv2:trustInfo xmlns:ms_asmv2="urn:schemas-microsoft-com:asm.v2">
+    <ms_asmv2:security>
+      <ms_asmv2:requestedPrivileges>
+        <ms_asmv2:requestedExecutionLevel level='requireAdministrator' uiAccess='false'/>
+      </ms_asmv2:requestedPrivileges>
+    </ms_asmv2:security>
+  </ms_asmv2:trustInfo>
+  <asmv3:application xmlns:asmv3="urn:schemas-microsoft-com:asm.v3">
+    <asmv3:windowsSettings>
+      <dpiAware xmlns="http://schemas.microsoft.com/SMI/2005/WindowsSettings">true/pm</dpiAware>
+      <dpiAwareness xmlns="http://schemas.microsoft.com/SMI/2016/WindowsSettings"> PerMonitorV2 </dpiAwareness>
+    </asmv3:windowsSettings>
+  </asmv3:application>
+</assembly>
+"#;
+
+    #[test]
+    fn test_manifest_x64() {
+        let manifest =
+            unsafe { get_manifest::<AutoDetect>(RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void, false) }
+                .unwrap();
+
+        assert!(manifest.text.starts_with("<?xml version='1.0'"));
+        assert!(manifest.text.ends_with("</assembly>\r\n") || manifest.text.ends_with("</assembly>\n"));
+        assert_eq!(manifest.identity, None);
+        assert!(manifest.dependencies.is_empty());
+        assert_eq!(manifest.execution_level, None);
+    }
+
+    #[test]
+    fn test_manifest_parse() {
+        let manifest = Manifest::parse(GAME_MANIFEST).unwrap();
+        assert_eq!(
+            manifest.identity,
+            Some(AssemblyIdentity {
+                name: Some("Sega.SonicHeroes"),
+                version: Some("1.2.3.4"),
+                ty: Some("win32"),
+                processor_architecture: Some("amd64"),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            manifest.dependencies,
+            [AssemblyIdentity {
+                name: Some("Microsoft.Windows.Common-Controls"),
+                version: Some("6.0.0.0"),
+                ty: Some("win32"),
+                processor_architecture: Some("*"),
+                public_key_token: Some("6595b64144ccf1df"),
+                language: Some("*"),
+            }]
+        );
+        assert_eq!(
+            manifest.execution_level,
+            Some(ExecutionLevel {
+                level: "requireAdministrator",
+                ui_access: Some(false)
+            })
+        );
+        assert_eq!(manifest.dpi_aware, Some("true/pm"));
+        assert_eq!(manifest.dpi_awareness, Some("PerMonitorV2"));
+
+        assert_eq!(Manifest::parse("<assembly><!-- x"), Err(PeError::MalformedResource));
+        assert_eq!(Manifest::parse("<assembly"), Err(PeError::MalformedResource));
+        assert_eq!(Manifest::parse("<assembly name='>"), Err(PeError::MalformedResource));
+
+        // A `>` in an attribute value does not end the tag.
+        let manifest = Manifest::parse(r#"<assembly><assemblyIdentity name="a>b" version='1'/></assembly>"#).unwrap();
+        let identity = manifest.identity.unwrap();
+        assert_eq!((identity.name, identity.version), (Some("a>b"), Some("1")));
+    }
+
+    #[test]
+    fn test_manifest_resource() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let view = PeView::new(&image, true).unwrap();
+        assert!(view.manifest(Some(1033)).is_ok());
+        assert_eq!(view.manifest(Some(0x407)), Err(PeError::ResourceNotFound));
+
+        // Rewrite the DLL's manifest in place, with a byte order mark and null padding.
+        let resource = view.resource(ResourceType::MANIFEST.into(), None, None).unwrap();
+        let data = resource.rva as usize..resource.rva as usize + resource.data.len();
+        image[data.clone()].fill(0);
+        image[data.start..data.start + 14].copy_from_slice(b"\xEF\xBB\xBF<assembly/>");
+        assert_eq!(
+            PeView::new(&image, true).unwrap().manifest(None).unwrap().text,
+            "<assembly/>"
+        );
+
+        // Not UTF-8.
+        let text = b"<assembly name='\xFF'/>";
+        image[data.start + 3..data.start + 3 + text.len()].copy_from_slice(text);
+        assert_eq!(
+            PeView::new(&image, true).unwrap().manifest(None),
+            Err(PeError::MalformedResource)
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{error::PeError, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*, utf16_str::Utf16Str};
+use core::{
+    ffi::c_void,
+    fmt::{Display, Formatter, Write},
+};
+
+/// `MESSAGE_RESOURCE_ENTRY` flag for text stored as UTF-16, rather than in the ANSI code page.
+const MESSAGE_RESOURCE_UNICODE: u16 = 0x0001;
+
+/// The text of a message from a message table (`RT_MESSAGETABLE`).
+///
+/// Messages usually end in `\r\n`, which is kept. Padding nulls are removed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MessageText<'a> {
+    /// Text in the ANSI code page of the system which compiled the table.
+    Ansi(&'a [u8]),
+    /// UTF-16 text.
+    Unicode(Utf16Str<'a>),
+}
+
+impl Display for MessageText<'_> {
+    /// Writes the text. ANSI text is decoded as Latin-1, as the code page is not known.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MessageText::Ansi(x) => x.iter().try_for_each(|&x| f.write_char(x.into())),
+            MessageText::Unicode(x) => Display::fmt(x, f),
+        }
+    }
+}
+
+/// Reads a little endian `u32` at `offset`.
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, PeError> {
+    let bytes = data.get(offset..offset + 4).ok_or(PeError::MalformedResource)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Finds a message in the message table (`RT_MESSAGETABLE`), like `FormatMessage` does.
+    ///
+    /// # Arguments
+    /// - `id` - The ID of the message, e.g. an error code.
+    /// - `language` - The language ID of the message table, or [`None`] for any language.
+    ///
+    /// # Remarks
+    /// The table is a list of blocks, each covering a range of IDs, followed by the
+    /// entries of each block. Inserts such as `%1` are left as is.
+    ///
+    /// # Returns
+    /// The text, [`PeError::ResourceNotFound`] if there is no such message, or
+    /// [`PeError::MalformedResource`] if the table is truncated.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn message(&self, id: u32, language: Option<u16>) -> Result<MessageText<'a>, PeError> {
+        let table = self.resource(ResourceType::MESSAGETABLE.into(), None, language)?.data;
+
+        // MESSAGE_RESOURCE_DATA, followed by MESSAGE_RESOURCE_BLOCK(s).
+        let number_of_blocks = read_u32(table, 0)? as usize;
+        for block in 0..number_of_blocks {
+            let block = 4 + block * 12;
+            let low_id = read_u32(table, block)?;
+            let high_id = read_u32(table, block + 4)?;
+            if id < low_id || id > high_id {
+                continue;
+            }
+
+            // MESSAGE_RESOURCE_ENTRY(s); skip to the one we want.
+            let mut offset = read_u32(table, block + 8)? as usize;
+            for _ in low_id..id {
+                let length = read_u32(table, offset)? as u16;
+                if length == 0 {
+                    return Err(PeError::MalformedResource);
+                }
+
+                offset += length as usize;
+            }
+
+            let header = read_u32(table, offset)?;
+            let (length, flags) = (header as u16 as usize, (header >> 16) as u16);
+            let text = table
+                .get(offset + 4..offset + length.max(4))
+                .ok_or(PeError::MalformedResource)?;
+
+            return Ok(if flags & MESSAGE_RESOURCE_UNICODE != 0 {
+                let text = Utf16Str::new(text);
+                let len = text.units().take_while(|&x| x != 0).count();
+                MessageText::Unicode(Utf16Str::new(&text.as_bytes()[..len * 2]))
+            } else {
+                MessageText::Ansi(&text[..text.iter().position(|&x| x == 0).unwrap_or(text.len())])
+            });
+        }
+
+        Err(PeError::ResourceNotFound)
+    }
+}
+
+/// Finds a message in the message table (`RT_MESSAGETABLE`), like `FormatMessage` does.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `id` - The ID of the message, e.g. an error code.
+/// - `language` - The language ID of the message table, or [`None`] for any language.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The text, or [`None`] if it could not be found. Use [`try_get_message`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_message<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    id: u32,
+    language: Option<u16>,
+    is_mapped: bool,
+) -> Option<MessageText<'a>> {
+    try_get_message::<W>(pe_start, id, language, is_mapped).ok()
+}
+
+/// Finds a message in the message table (`RT_MESSAGETABLE`), like `FormatMessage` does.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `id` - The ID of the message, e.g. an error code.
+/// - `language` - The language ID of the message table, or [`None`] for any language.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The text, [`PeError::ResourceNotFound`] if there is no such message, or another
+/// [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_message<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    id: u32,
+    language: Option<u16>,
+    is_mapped: bool,
+) -> Result<MessageText<'a>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.message(id, language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        name_or_id::NameOrId,
+        pe_view::PeView,
+        pe_width::AutoDetect,
+        utils::{
+            map_image::map_image,
+            test_utils::{add_resources, RELOADED_BOOTSTRAPPER_DLL_X64},
+        },
+    };
+    use alloc::{string::ToString, vec::Vec};
+
+    /// A message table with a block for each (low ID, messages); messages are (unicode, text).
+    fn table(blocks: &[(u32, &[(bool, &str)])]) -> Vec<u8> {
+        let mut entries = Vec::new();
+        let mut headers = (blocks.len() as u32).to_le_bytes().to_vec();
+        let start = 4 + blocks.len() * 12;
+        for &(low_id, messages) in blocks {
+            let high_id = low_id + messages.len() as u32 - 1;
+            let offset = (start + entries.len()) as u32;
+            headers.extend_from_slice(&[low_id, high_id, offset].map(u32::to_le_bytes).concat());
+
+            for &(unicode, text) in messages {
+                let mut text: Vec<u8> = match unicode {
+                    true => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+                    false => text.as_bytes().to_vec(),
+                };
+                text.resize((text.len() + 1).next_multiple_of(4), 0);
+                entries.extend_from_slice(&(text.len() as u16 + 4).to_le_bytes());
+                entries.extend_from_slice(&u16::from(unicode).to_le_bytes());
+                entries.extend_from_slice(&text);
+            }
+        }
+
+        [headers, entries].concat()
+    }
+
+    #[test]
+    fn test_message() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let english = table(&[
+            (1, &[(true, "One.\r\n"), (false, "Two.\r\n"), (true, "Three %1.\r\n")]),
+            (0xC000_0005, &[(true, "Access violation.\r\n")]),
+        ]);
+        let german = table(&[(1, &[(true, "Eins.\r\n")])]);
+        add_resources(
+            &mut image,
+            &[
+                (ResourceType::MESSAGETABLE.into(), NameOrId::Id(1), 0x409, &english),
+                (ResourceType::MESSAGETABLE.into(), NameOrId::Id(1), 0x407, &german),
+            ],
+        );
+
+        let message = unsafe { get_message::<AutoDetect>(image.as_ptr() as *const c_void, 3, None, true) }.unwrap();
+        assert!(matches!(message, MessageText::Unicode(x) if x == "Three %1.\r\n"));
+
+        let view = PeView::new(&image, true).unwrap();
+        assert_eq!(view.message(2, None), Ok(MessageText::Ansi(b"Two.\r\n")));
+        assert_eq!(view.message(1, None).unwrap().to_string(), "One.\r\n");
+        assert_eq!(view.message(1, Some(0x407)).unwrap().to_string(), "Eins.\r\n");
+        assert_eq!(
+            view.message(0xC000_0005, None).unwrap().to_string(),
+            "Access violation.\r\n"
+        );
+        assert_eq!(view.message(4, None), Err(PeError::ResourceNotFound));
+        assert_eq!(view.message(0, None), Err(PeError::ResourceNotFound));
+        assert_eq!(view.message(2, Some(0x407)), Err(PeError::ResourceNotFound));
+        assert_eq!(MessageText::Ansi(b"caf\xE9").to_string(), "café");
+    }
+
+    #[test]
+    fn test_message_malformed() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+
+        // An entry with a length of 0 would never advance.
+        let mut looping = table(&[(1, &[(false, "A"), (false, "B")])]);
+        looping[16..18].copy_from_slice(&[0, 0]);
+        add_resources(
+            &mut image,
+            &[(ResourceType::MESSAGETABLE.into(), NameOrId::Id(1), 0, &looping)],
+        );
+        let view = PeView::new(&image, true).unwrap();
+        assert_eq!(view.message(2, None), Err(PeError::MalformedResource));
+
+        // More blocks than there is data for.
+        add_resources(
+            &mut image,
+            &[(ResourceType::MESSAGETABLE.into(), NameOrId::Id(1), 0, &[2, 0, 0, 0])],
+        );
+        assert_eq!(
+            PeView::new(&image, true).unwrap().message(1, None),
+            Err(PeError::MalformedResource)
+        );
+    }
+}
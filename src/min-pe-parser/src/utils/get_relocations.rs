@@ -0,0 +1,280 @@
+use crate::{error::PeError, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*};
+use alloc::vec::Vec;
+use core::{ffi::c_void, mem::size_of};
+use simple_endian::LittleEndian;
+
+/// A single fixup from the base relocation table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Relocation {
+    /// How the value at [`Relocation::rva`] should be adjusted.
+    pub ty: RelocationType,
+    /// RVA of the value to adjust.
+    pub rva: u32,
+    /// For [`RelocationType::HIGHADJ`], the low 16 bits of the 32-bit value whose high
+    /// 16 bits are at [`Relocation::rva`], taken from the following entry. Otherwise 0.
+    pub adjust: u16,
+}
+
+/// Iterator over the fixups of the base relocation table, in the order they are stored.
+/// [`RelocationType::ABSOLUTE`] padding entries are included. A malformed block yields
+/// an error, after which iteration stops.
+///
+/// Created with [`PeHeaders::relocations`].
+#[derive(Clone, Debug)]
+pub struct Relocations<'a, W: PeWidth, const CHECKED: bool> {
+    headers: PeHeaders<'a, W, CHECKED>,
+    /// Offset of the next entry.
+    offset: usize,
+    /// Offset of the end of the current block.
+    block_end: usize,
+    /// Offset of the end of the base relocation table.
+    end: usize,
+    /// RVA of the page the current block applies to.
+    block_rva: u32,
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Returns an iterator over all fixups in the base relocation table.
+    ///
+    /// # Returns
+    /// The iterator, which is empty if the file has no base relocation table.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn relocations(&self) -> Result<Relocations<'a, W, CHECKED>, PeError> {
+        let (table, size) = self
+            .data_directory_offset_if_present(DataDirectoryType::BaseRelocationTable)?
+            .unwrap_or((0, 0));
+
+        // Ensure the whole table is there, so the iterator only has to worry about the block sizes.
+        self.bytes(table, size as usize)?;
+        Ok(Relocations {
+            headers: *self,
+            offset: table,
+            block_end: table,
+            end: table + size as usize,
+            block_rva: 0,
+        })
+    }
+}
+
+impl<W: PeWidth, const CHECKED: bool> Relocations<'_, W, CHECKED> {
+    /// Reads the entry at the current offset, and advances past it.
+    fn next_entry(&mut self) -> Result<Option<u16>, PeError> {
+        if self.offset + size_of::<u16>() > self.block_end {
+            return Ok(None);
+        }
+
+        let entry: LittleEndian<u16> = self.headers.read(self.offset)?;
+        self.offset += size_of::<u16>();
+        Ok(Some(entry.into()))
+    }
+
+    /// Reads the next fixup, moving onto the next block when the current one runs out.
+    fn next_relocation(&mut self) -> Result<Option<Relocation>, PeError> {
+        loop {
+            if let Some(entry) = self.next_entry()? {
+                let ty = RelocationType::new((entry >> 12) as u8);
+                let adjust = match ty {
+                    RelocationType::HIGHADJ => self.next_entry()?.ok_or(PeError::MalformedRelocation)?,
+                    _ => 0,
+                };
+
+                return Ok(Some(Relocation {
+                    ty,
+                    rva: self.block_rva.wrapping_add((entry & 0xFFF).into()),
+                    adjust,
+                }));
+            }
+
+            // Move onto the next block.
+            self.offset = self.block_end;
+            if self.offset == self.end {
+                return Ok(None);
+            }
+
+            if self.offset + size_of::<IMAGE_BASE_RELOCATION>() > self.end {
+                return Err(PeError::Truncated);
+            }
+
+            let block: IMAGE_BASE_RELOCATION = self.headers.read(self.offset)?;
+            let size = block.size_of_block() as usize;
+            if size < size_of::<IMAGE_BASE_RELOCATION>() || size > self.end - self.offset {
+                return Err(PeError::MalformedRelocation);
+            }
+
+            self.block_rva = block.virtual_address();
+            self.block_end = self.offset + size;
+            self.offset += size_of::<IMAGE_BASE_RELOCATION>();
+        }
+    }
+}
+
+impl<W: PeWidth, const CHECKED: bool> Iterator for Relocations<'_, W, CHECKED> {
+    type Item = Result<Relocation, PeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let relocation = self.next_relocation().transpose();
+        if let Some(Err(_)) = relocation {
+            // Stop after the first error.
+            self.offset = self.end;
+            self.block_end = self.end;
+        }
+
+        relocation
+    }
+}
+
+/// Get all fixups in the base relocation table.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Remarks
+/// This function assumes that the PE file is valid and that the pointer is not null.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+///
+/// # Returns
+/// All fixups, or an empty [`Vec`] if they could not be read.
+/// Use [`try_get_relocations`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_relocations<W: PeWidth>(pe_start: *const c_void, is_mapped: bool) -> Vec<Relocation> {
+    try_get_relocations::<W>(pe_start, is_mapped)
+        .and_then(Iterator::collect)
+        .unwrap_or_default()
+}
+
+/// Returns an iterator over all fixups in the base relocation table.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The iterator, which is empty if the file has no base relocation table.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_relocations<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<Relocations<'a, W, false>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.relocations()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::{AutoDetect, Pe32, Pe64},
+        utils::{
+            aligned_buffer::AlignedBuffer,
+            map_image::map_image,
+            test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+        },
+    };
+    use alloc::vec;
+
+    #[test]
+    fn test_relocations_x64() {
+        let relocations =
+            unsafe { get_relocations::<Pe64>(RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void, false) };
+
+        assert_eq!(relocations.len(), 124);
+        assert_eq!(
+            relocations.iter().filter(|x| x.ty == RelocationType::DIR64).count(),
+            121
+        );
+        assert_eq!(
+            relocations[0],
+            Relocation {
+                ty: RelocationType::DIR64,
+                rva: 0x1b628,
+                adjust: 0
+            }
+        );
+        assert_eq!(relocations[1].rva, 0x1b630);
+
+        // Same result from the mapped image.
+        let image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let mapped = unsafe { get_relocations::<AutoDetect>(image.as_ptr() as *const c_void, true) };
+        assert_eq!(mapped, relocations);
+    }
+
+    #[test]
+    fn test_relocations_x86() {
+        let relocations =
+            unsafe { get_relocations::<Pe32>(RELOADED_BOOTSTRAPPER_DLL_X86.as_ptr() as *const c_void, false) };
+
+        assert_eq!(relocations.len(), 1970);
+        assert_eq!(
+            relocations.iter().filter(|x| x.ty == RelocationType::HIGHLOW).count(),
+            1959
+        );
+        assert_eq!(relocations[0].rva, 0x1001);
+        assert_eq!(relocations[3].rva, 0x101c);
+    }
+
+    #[test]
+    fn test_relocations_highadj_and_malformed_block() {
+        let mut bytes = AlignedBuffer::from(RELOADED_BOOTSTRAPPER_DLL_X64);
+
+        // Rewrite the first block as a HIGHADJ (with its extra entry) and an ABSOLUTE pad,
+        // followed by a block that claims to be larger than the table.
+        let (table, _) = PeView::new(&bytes, false)
+            .unwrap()
+            .data_directory_offset(DataDirectoryType::BaseRelocationTable)
+            .unwrap();
+        let entries: [u16; 9] = [0x3000, 0, 14, 0, 0x4123, 0x8765, 0, 0x5000, 0];
+        for (x, entry) in entries.into_iter().enumerate() {
+            bytes[table + x * 2..table + x * 2 + 2].copy_from_slice(&entry.to_le_bytes());
+        }
+        bytes[table + 18..table + 22].copy_from_slice(&0x1000u32.to_le_bytes());
+
+        let relocations: Vec<_> = PeView::new(&bytes, false).unwrap().relocations().unwrap().collect();
+        assert_eq!(
+            relocations,
+            vec![
+                Ok(Relocation {
+                    ty: RelocationType::HIGHADJ,
+                    rva: 0x3123,
+                    adjust: 0x8765
+                }),
+                Ok(Relocation {
+                    ty: RelocationType::ABSOLUTE,
+                    rva: 0x3000,
+                    adjust: 0
+                }),
+                Err(PeError::MalformedRelocation),
+            ]
+        );
+        assert!(unsafe { get_relocations::<AutoDetect>(bytes.as_ptr() as *const c_void, false) }.is_empty());
+
+        // A HIGHADJ without its extra entry, at the end of the block.
+        bytes[table + 4..table + 8].copy_from_slice(&10u32.to_le_bytes());
+        let mut relocations = PeView::new(&bytes, false).unwrap().relocations().unwrap();
+        assert_eq!(relocations.next(), Some(Err(PeError::MalformedRelocation)));
+        assert_eq!(relocations.next(), None);
+    }
+
+    #[test]
+    fn test_relocations_without_table() {
+        let mut bytes = AlignedBuffer::from(RELOADED_BOOTSTRAPPER_DLL_X64);
+        let view = PeView::new(&bytes, false).unwrap();
+        let directory = view.data_directories()[DataDirectoryType::BaseRelocationTable as usize..].as_ptr() as usize
+            - view.pe_start() as usize;
+
+        bytes[directory..directory + 8].fill(0);
+        assert_eq!(PeView::new(&bytes, false).unwrap().relocations().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_try_get_relocations_not_a_pe() {
+        let not_a_pe = [0u32; 64];
+        assert!(unsafe { try_get_relocations::<AutoDetect>(not_a_pe.as_ptr() as *const c_void, false) }.is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    error::PeError, name_or_id::NameOrId, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*, utf16_str::Utf16Str,
+};
+use core::ffi::c_void;
+
+/// Number of strings in each `RT_STRING` block.
+const STRINGS_PER_BLOCK: u16 = 16;
+
+/// Reads the length of the string at the start of `data`, in bytes.
+fn read_length(data: &[u8]) -> Result<usize, PeError> {
+    let length = data.get(..2).ok_or(PeError::MalformedResource)?;
+    Ok(u16::from_le_bytes([length[0], length[1]]) as usize * 2)
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Finds a string from the string table (`RT_STRING`), like `LoadStringW` does.
+    ///
+    /// # Arguments
+    /// - `id` - The ID of the string.
+    /// - `language` - The language ID of the string, or [`None`] for any language.
+    ///
+    /// # Remarks
+    /// Strings are stored in blocks of 16; string `id` is entry `id % 16` of the block
+    /// with ID `id / 16 + 1`. Each entry is a UTF-16 length, followed by that many code units.
+    ///
+    /// IDs missing from a block are stored as empty strings, so they cannot be told apart
+    /// from strings which are really empty; both return an empty string.
+    ///
+    /// # Returns
+    /// The string, [`PeError::ResourceNotFound`] if there is no block for the ID,
+    /// or [`PeError::MalformedResource`] if the block is truncated.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn resource_string(&self, id: u16, language: Option<u16>) -> Result<Utf16Str<'a>, PeError> {
+        let block_id = NameOrId::Id(id / STRINGS_PER_BLOCK + 1);
+        let block = self.resource(ResourceType::STRING.into(), Some(block_id), language)?;
+
+        // Skip the strings before the one we want.
+        let mut data = block.data;
+        for _ in 0..id % STRINGS_PER_BLOCK {
+            let length = read_length(data)?;
+            data = data.get(2 + length..).ok_or(PeError::MalformedResource)?;
+        }
+
+        let length = read_length(data)?;
+        let string = data.get(2..2 + length).ok_or(PeError::MalformedResource)?;
+        Ok(Utf16Str::new(string))
+    }
+}
+
+/// Finds a string from the string table (`RT_STRING`), like `LoadStringW` does.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `id` - The ID of the string.
+/// - `language` - The language ID of the string, or [`None`] for any language.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The string, or [`None`] if it could not be found. Use [`try_get_resource_string`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_resource_string<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    id: u16,
+    language: Option<u16>,
+    is_mapped: bool,
+) -> Option<Utf16Str<'a>> {
+    try_get_resource_string::<W>(pe_start, id, language, is_mapped).ok()
+}
+
+/// Finds a string from the string table (`RT_STRING`), like `LoadStringW` does.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `id` - The ID of the string.
+/// - `language` - The language ID of the string, or [`None`] for any language.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The string, [`PeError::ResourceNotFound`] if there is no block for the ID, or another
+/// [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_resource_string<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    id: u16,
+    language: Option<u16>,
+    is_mapped: bool,
+) -> Result<Utf16Str<'a>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.resource_string(id, language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::AutoDetect,
+        utils::{
+            map_image::map_image,
+            test_utils::{add_resources, counted_utf16, RELOADED_BOOTSTRAPPER_DLL_X64},
+        },
+    };
+    use alloc::vec::Vec;
+
+    /// A string block, with the given strings at the start and the rest empty.
+    fn block(strings: &[&str]) -> Vec<u8> {
+        (0..STRINGS_PER_BLOCK as usize)
+            .flat_map(|x| counted_utf16(strings.get(x).unwrap_or(&"")))
+            .collect()
+    }
+
+    #[test]
+    fn test_resource_string() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let english = block(&["Zero", "", "Failed to load the mod loader."]);
+        let german = block(&["Null"]);
+        let second = block(&["Sixteen"]);
+        add_resources(
+            &mut image,
+            &[
+                (ResourceType::STRING.into(), NameOrId::Id(1), 0x409, &english),
+                (ResourceType::STRING.into(), NameOrId::Id(1), 0x407, &german),
+                (ResourceType::STRING.into(), NameOrId::Id(2), 0x409, &second),
+            ],
+        );
+
+        let string =
+            unsafe { get_resource_string::<AutoDetect>(image.as_ptr() as *const c_void, 2, None, true) }.unwrap();
+        assert_eq!(string, "Failed to load the mod loader.");
+
+        let view = PeView::new(&image, true).unwrap();
+        assert_eq!(view.resource_string(0, Some(0x409)).unwrap(), "Zero");
+        assert_eq!(view.resource_string(0, Some(0x407)).unwrap(), "Null");
+        assert_eq!(view.resource_string(16, None).unwrap(), "Sixteen");
+        assert!(view.resource_string(1, None).unwrap().is_empty());
+        assert!(view.resource_string(15, None).unwrap().is_empty());
+        assert!(view.resource_string(2, Some(0x407)).unwrap().is_empty());
+        assert_eq!(view.resource_string(16, Some(0x407)), Err(PeError::ResourceNotFound));
+        assert_eq!(view.resource_string(32, None), Err(PeError::ResourceNotFound));
+    }
+
+    #[test]
+    fn test_resource_string_malformed() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+
+        // The first string claims to be longer than the block.
+        add_resources(
+            &mut image,
+            &[(ResourceType::STRING.into(), NameOrId::Id(1), 0, &[8, 0, b'a', 0])],
+        );
+        let view = PeView::new(&image, true).unwrap();
+        assert_eq!(view.resource_string(0, None), Err(PeError::MalformedResource));
+        assert_eq!(view.resource_string(1, None), Err(PeError::MalformedResource));
+    }
+}
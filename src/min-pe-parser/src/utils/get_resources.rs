@@ -0,0 +1,450 @@
+use crate::{
+    error::PeError, name_or_id::NameOrId, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*, utf16_str::Utf16Str,
+};
+use alloc::vec::Vec;
+use core::{ffi::c_void, mem::size_of};
+use simple_endian::LittleEndian;
+
+/// The name of a type, or of a resource, in the resource directory.
+pub type ResourceId<'a> = NameOrId<Utf16Str<'a>>;
+
+impl<N> From<ResourceType> for NameOrId<N> {
+    fn from(ty: ResourceType) -> Self {
+        NameOrId::Id(ty.value())
+    }
+}
+
+impl ResourceId<'_> {
+    /// Checks if this is the given type or resource. Names are compared ignoring ASCII case,
+    /// like the loader does.
+    pub fn matches(&self, id: NameOrId<&str>) -> bool {
+        match (*self, id) {
+            (NameOrId::Name(name), NameOrId::Name(x)) => name.eq_ignore_ascii_case(x),
+            (NameOrId::Id(id), NameOrId::Id(x)) => id == x,
+            _ => false,
+        }
+    }
+}
+
+/// A resource, i.e. a leaf of the type → name → language tree in the resource directory.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Resource<'a> {
+    /// The type of the resource, e.g. [`ResourceType::VERSION`].
+    pub ty: ResourceId<'a>,
+    /// The name of the resource.
+    pub name: ResourceId<'a>,
+    /// The language ID of the resource, e.g. `0x409` for English (United States).
+    pub language: u16,
+    /// The code page of the data, as stored in the data entry. Usually 0.
+    pub code_page: u32,
+    /// RVA of the data.
+    pub rva: u32,
+    /// The data.
+    pub data: &'a [u8],
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Get all resources in the resource directory, in the order they are stored.
+    ///
+    /// # Remarks
+    /// Entries which do not follow the type → name → language layout (e.g. a data entry
+    /// where a subdirectory is expected, or a named language) are skipped.
+    ///
+    /// # Returns
+    /// All resources, or an empty [`Vec`] if the file has no resource directory.
+    /// [`PeError::MalformedResource`] is returned if subdirectories are shared, as that could
+    /// list each resource many times over.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn resources(&self) -> Result<Vec<Resource<'a>>, PeError> {
+        let Some((root, size)) = self.data_directory_offset_if_present(DataDirectoryType::ResourceTable)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut budget = Self::resource_budget(size);
+        let mut resources = Vec::new();
+        for ty in self
+            .resource_entries(root, 0, &mut budget)?
+            .iter()
+            .filter(|x| x.is_directory())
+        {
+            for name in self
+                .resource_entries(root, ty.offset(), &mut budget)?
+                .iter()
+                .filter(|x| x.is_directory())
+            {
+                for language in self.resource_entries(root, name.offset(), &mut budget)? {
+                    if let Some(resource) = self.resource_data(root, ty, name, language)? {
+                        resources.push(resource);
+                    }
+                }
+            }
+        }
+
+        Ok(resources)
+    }
+
+    /// Finds a resource by its type, name and language.
+    ///
+    /// # Arguments
+    /// - `ty` - The type of the resource, e.g. `ResourceType::VERSION.into()`.
+    /// - `name` - The name of the resource, or [`None`] for any name.
+    /// - `language` - The language ID of the resource, or [`None`] for any language.
+    ///
+    /// # Remarks
+    /// If several resources match, the first one stored is returned.
+    ///
+    /// # Returns
+    /// The resource, [`PeError::ResourceNotFound`] if there is no such resource (or no resource
+    /// directory), or another [`PeError`] if the resource directory is malformed, e.g.
+    /// [`PeError::MalformedResource`] if its subdirectories are shared.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn resource(
+        &self,
+        ty: NameOrId<&str>,
+        name: Option<NameOrId<&str>>,
+        language: Option<u16>,
+    ) -> Result<Resource<'a>, PeError> {
+        let (root, size) = self
+            .data_directory_offset_if_present(DataDirectoryType::ResourceTable)?
+            .ok_or(PeError::ResourceNotFound)?;
+        let mut budget = Self::resource_budget(size);
+        for ty_entry in self.resource_entries(root, 0, &mut budget)? {
+            if !ty_entry.is_directory() || !self.resource_id(root, ty_entry)?.matches(ty) {
+                continue;
+            }
+
+            for name_entry in self.resource_entries(root, ty_entry.offset(), &mut budget)? {
+                if !name_entry.is_directory() {
+                    continue;
+                }
+
+                if let Some(name) = name {
+                    if !self.resource_id(root, name_entry)?.matches(name) {
+                        continue;
+                    }
+                }
+
+                for language_entry in self.resource_entries(root, name_entry.offset(), &mut budget)? {
+                    if language.is_some_and(|x| language_entry.is_named() || language_entry.id() != x) {
+                        continue;
+                    }
+
+                    if let Some(resource) = self.resource_data(root, ty_entry, name_entry, language_entry)? {
+                        return Ok(resource);
+                    }
+                }
+            }
+        }
+
+        Err(PeError::ResourceNotFound)
+    }
+
+    /// Returns how many directory entries a walk of a resource directory of the given size may visit.
+    ///
+    /// Every entry takes up space in the directory, so visiting more entries than fit means
+    /// subdirectories are shared, which would otherwise multiply the work (and the output).
+    fn resource_budget(size: u32) -> usize {
+        size as usize / size_of::<IMAGE_RESOURCE_DIRECTORY_ENTRY>()
+    }
+
+    /// Returns the entries of the resource directory at `offset`, from the start
+    /// of the resource directory at `root`, taking them out of the walk's `budget`.
+    ///
+    /// # Returns
+    /// [`PeError::MalformedResource`] if the budget is exceeded.
+    fn resource_entries(
+        &self,
+        root: usize,
+        offset: u32,
+        budget: &mut usize,
+    ) -> Result<&'a [IMAGE_RESOURCE_DIRECTORY_ENTRY], PeError> {
+        let directory: IMAGE_RESOURCE_DIRECTORY = self.read(root + offset as usize)?;
+        let entries = self.slice(
+            root + offset as usize + size_of::<IMAGE_RESOURCE_DIRECTORY>(),
+            directory.number_of_entries(),
+        )?;
+
+        *budget = budget.checked_sub(entries.len()).ok_or(PeError::MalformedResource)?;
+        Ok(entries)
+    }
+
+    /// Returns the type or name of a resource directory entry.
+    pub(crate) fn resource_id(
+        &self,
+        root: usize,
+        entry: &IMAGE_RESOURCE_DIRECTORY_ENTRY,
+    ) -> Result<ResourceId<'a>, PeError> {
+        if !entry.is_named() {
+            return Ok(NameOrId::Id(entry.id()));
+        }
+
+        // Length prefixed, in UTF-16 code units.
+        let offset = root + entry.name_offset() as usize;
+        let len: LittleEndian<u16> = self.read(offset)?;
+        let name = self.bytes(offset + size_of::<u16>(), u16::from(len) as usize * 2)?;
+        Ok(NameOrId::Name(Utf16Str::new(name)))
+    }
+
+    /// Reads the data entry of a resource, or [`None`] if the entries do not form
+    /// a directory → directory → data path with a language ID.
+    fn resource_data(
+        &self,
+        root: usize,
+        ty: &IMAGE_RESOURCE_DIRECTORY_ENTRY,
+        name: &IMAGE_RESOURCE_DIRECTORY_ENTRY,
+        language: &IMAGE_RESOURCE_DIRECTORY_ENTRY,
+    ) -> Result<Option<Resource<'a>>, PeError> {
+        if !ty.is_directory() || !name.is_directory() || language.is_directory() || language.is_named() {
+            return Ok(None);
+        }
+
+        let entry: IMAGE_RESOURCE_DATA_ENTRY = self.read(root + language.offset() as usize)?;
+        let data = self.bytes(self.rva_to_offset(entry.offset_to_data())?, entry.size() as usize)?;
+        Ok(Some(Resource {
+            ty: self.resource_id(root, ty)?,
+            name: self.resource_id(root, name)?,
+            language: language.id(),
+            code_page: entry.code_page(),
+            rva: entry.offset_to_data(),
+            data,
+        }))
+    }
+}
+
+/// Get all resources in the resource directory.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Remarks
+/// This function assumes that the PE file is valid and that the pointer is not null.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// All resources, or an empty [`Vec`] if they could not be read.
+/// Use [`try_get_resources`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_resources<'a, W: PeWidth>(pe_start: *const c_void, is_mapped: bool) -> Vec<Resource<'a>> {
+    try_get_resources::<W>(pe_start, is_mapped).unwrap_or_default()
+}
+
+/// Get all resources in the resource directory.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// All resources, or an empty [`Vec`] if the file has no resource directory.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_resources<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<Vec<Resource<'a>>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.resources()
+}
+
+/// Finds a resource by its type, name and language.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `ty` - The type of the resource, e.g. `ResourceType::VERSION.into()`.
+/// - `name` - The name of the resource, or [`None`] for any name.
+/// - `language` - The language ID of the resource, or [`None`] for any language.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The resource, or [`None`] if it could not be found. Use [`try_get_resource`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_resource<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    ty: NameOrId<&str>,
+    name: Option<NameOrId<&str>>,
+    language: Option<u16>,
+    is_mapped: bool,
+) -> Option<Resource<'a>> {
+    try_get_resource::<W>(pe_start, ty, name, language, is_mapped).ok()
+}
+
+/// Finds a resource by its type, name and language.
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `ty` - The type of the resource, e.g. `ResourceType::VERSION.into()`.
+/// - `name` - The name of the resource, or [`None`] for any name.
+/// - `language` - The language ID of the resource, or [`None`] for any language.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The resource, [`PeError::ResourceNotFound`] if there is no such resource, or another
+/// [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_resource<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    ty: NameOrId<&str>,
+    name: Option<NameOrId<&str>>,
+    language: Option<u16>,
+    is_mapped: bool,
+) -> Result<Resource<'a>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.resource(ty, name, language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::{AutoDetect, Pe32},
+        utils::{
+            aligned_buffer::AlignedBuffer,
+            map_image::map_image,
+            test_utils::{
+                add_resources, add_table, directory_entry, RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86,
+            },
+        },
+    };
+
+    #[test]
+    fn test_resources_x64() {
+        let resources =
+            unsafe { get_resources::<AutoDetect>(RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void, false) };
+
+        assert_eq!(resources.len(), 2);
+        assert_eq!(resources[0].ty, NameOrId::Id(ResourceType::VERSION.value()));
+        assert_eq!(resources[0].name, NameOrId::Id(1));
+        assert_eq!(resources[0].language, 2057);
+        assert_eq!(resources[0].rva, 0x270a0);
+        assert_eq!(resources[0].data.len(), 812);
+        assert_eq!(&resources[0].data[6..12], b"V\0S\0_\0");
+        assert_eq!(resources[1].ty, NameOrId::Id(ResourceType::MANIFEST.value()));
+        assert_eq!(resources[1].name, NameOrId::Id(2));
+        assert_eq!(resources[1].language, 1033);
+        assert_eq!(resources[1].data.len(), 145);
+
+        // Same result from the mapped image.
+        let image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        assert_eq!(PeView::new(&image, true).unwrap().resources(), Ok(resources));
+    }
+
+    #[test]
+    fn test_resource_x86() {
+        let pe_start = RELOADED_BOOTSTRAPPER_DLL_X86.as_ptr() as *const c_void;
+        let manifest =
+            unsafe { get_resource::<Pe32>(pe_start, ResourceType::MANIFEST.into(), None, None, false) }.unwrap();
+        assert_eq!(manifest.rva, 0x1d3d0);
+        assert!(manifest.data.starts_with(b"<?xml"));
+
+        let version = unsafe {
+            try_get_resource::<Pe32>(
+                pe_start,
+                ResourceType::VERSION.into(),
+                Some(NameOrId::Id(1)),
+                Some(2057),
+                false,
+            )
+        };
+        assert_eq!(version.unwrap().data.len(), 812);
+
+        for (name, language) in [(Some(NameOrId::Id(2)), None), (None, Some(1033))] {
+            assert_eq!(
+                unsafe { try_get_resource::<Pe32>(pe_start, ResourceType::VERSION.into(), name, language, false) },
+                Err(PeError::ResourceNotFound)
+            );
+        }
+    }
+
+    #[test]
+    fn test_resources_without_resource_directory() {
+        let mut bytes = AlignedBuffer::from(RELOADED_BOOTSTRAPPER_DLL_X86);
+        let directory = directory_entry(&bytes, DataDirectoryType::ResourceTable);
+
+        bytes[directory..directory + 8].fill(0);
+        let view = PeView::new(&bytes, false).unwrap();
+        assert_eq!(view.resources(), Ok(Vec::new()));
+        assert_eq!(
+            view.resource(ResourceType::MANIFEST.into(), None, None),
+            Err(PeError::ResourceNotFound)
+        );
+    }
+
+    #[test]
+    fn test_resources_shared_subdirectories() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+
+        // Each level has 4 entries pointing at the same subdirectory, so 64 resources
+        // are listed from a single data entry.
+        add_table(&mut image, DataDirectoryType::ResourceTable, |rva| {
+            let directory = |next: u32| {
+                let mut directory = [0u32, 0, 0, 4 << 16].map(u32::to_le_bytes).concat();
+                for id in 1..=4 {
+                    directory.extend_from_slice(&[id, next].map(u32::to_le_bytes).concat());
+                }
+                directory
+            };
+            [
+                directory(IMAGE_RESOURCE_DATA_IS_DIRECTORY | 48),
+                directory(IMAGE_RESOURCE_DATA_IS_DIRECTORY | 96),
+                directory(144),
+                [rva + 160, 4, 0, 0].map(u32::to_le_bytes).concat(),
+                b"data".to_vec(),
+            ]
+            .concat()
+        });
+
+        let view = PeView::new(&image, true).unwrap();
+        assert_eq!(view.resources(), Err(PeError::MalformedResource));
+        // A lookup which stops early is fine, but a search through every name is not.
+        assert_eq!(view.resource(NameOrId::Id(1), None, None).unwrap().data, b"data");
+        assert_eq!(
+            view.resource(NameOrId::Id(4), None, Some(5)),
+            Err(PeError::MalformedResource)
+        );
+    }
+
+    #[test]
+    fn test_named_resources() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        add_resources(
+            &mut image,
+            &[
+                (NameOrId::Name("TEXTFILE"), NameOrId::Name("README"), 0x409, b"hello"),
+                (NameOrId::Name("TEXTFILE"), NameOrId::Id(7), 0x407, b"hallo"),
+                (ResourceType::RCDATA.into(), NameOrId::Name("Config"), 0, b"{}"),
+            ],
+        );
+
+        let view = PeView::new(&image, true).unwrap();
+        let resources = view.resources().unwrap();
+        assert_eq!(resources.len(), 3);
+        assert!(matches!(resources[0].ty, NameOrId::Name(x) if x == "TEXTFILE"));
+        assert!(matches!(resources[0].name, NameOrId::Name(x) if x == "README"));
+        assert_eq!(resources[1].name, NameOrId::Id(7));
+        assert_eq!(resources[1].data, b"hallo");
+
+        let config = view
+            .resource(ResourceType::RCDATA.into(), Some(NameOrId::Name("CONFIG")), None)
+            .unwrap();
+        assert_eq!(config.data, b"{}");
+        assert_eq!(config.language, 0);
+
+        // The first name with the language.
+        let hallo = view.resource(NameOrId::Name("textfile"), None, Some(0x407)).unwrap();
+        assert_eq!(hallo.name, NameOrId::Id(7));
+    }
+}
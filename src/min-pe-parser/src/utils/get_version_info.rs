@@ -0,0 +1,276 @@
+use crate::{error::PeError, pe_headers::PeHeaders, pe_width::PeWidth, prelude::*, utf16_str::Utf16Str};
+use alloc::vec::Vec;
+use core::{ffi::c_void, mem::size_of, ptr::read_unaligned};
+
+/// The decoded `VS_VERSIONINFO` resource (`RT_VERSION`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionInfo<'a> {
+    /// The language independent version information, if present.
+    pub fixed: Option<VS_FIXEDFILEINFO>,
+    /// The `StringFileInfo` tables, one per language and code page.
+    pub string_tables: Vec<VersionStringTable<'a>>,
+}
+
+/// A `StringTable` of the `StringFileInfo` block, e.g. `ProductName` and `FileVersion`,
+/// in a single language and code page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionStringTable<'a> {
+    /// The language ID of the strings, e.g. `0x409` for English (United States).
+    pub language: u16,
+    /// The code page of the strings, e.g. `1200` for Unicode.
+    pub code_page: u16,
+    /// The key/value pairs, in the order they are stored.
+    pub strings: Vec<(Utf16Str<'a>, Utf16Str<'a>)>,
+}
+
+impl<'a> VersionStringTable<'a> {
+    /// Returns the value of the string named `key`, e.g. `ProductName`.
+    pub fn get(&self, key: &str) -> Option<Utf16Str<'a>> {
+        self.strings.iter().find(|(x, _)| *x == key).map(|(_, value)| *value)
+    }
+}
+
+impl<'a> VersionInfo<'a> {
+    /// Returns the value of the string named `key`, e.g. `ProductName`, from the first
+    /// string table which has it.
+    pub fn string(&self, key: &str) -> Option<Utf16Str<'a>> {
+        self.string_tables.iter().find_map(|x| x.get(key))
+    }
+}
+
+/// A block of the `VS_VERSIONINFO` tree. Every block has the same header,
+/// followed by a key, an optional value, and child blocks.
+struct VersionBlock<'a> {
+    key: Utf16Str<'a>,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+impl<'a> VersionBlock<'a> {
+    /// Parses the block at the start of `data`, which must be 4 byte aligned relative
+    /// to the start of the resource.
+    ///
+    /// # Returns
+    /// The block, and the data after it.
+    fn parse(data: &'a [u8]) -> Result<(Self, &'a [u8]), PeError> {
+        let header = data.get(..6).ok_or(PeError::Truncated)?;
+        let length = u16::from_le_bytes([header[0], header[1]]) as usize;
+        let value_length = u16::from_le_bytes([header[2], header[3]]) as usize;
+        let is_text = u16::from_le_bytes([header[4], header[5]]) == 1;
+        if length < header.len() {
+            return Err(PeError::MalformedResource);
+        }
+
+        let block = data.get(..length).ok_or(PeError::Truncated)?;
+        let (key, _) = Utf16Str::from_nul_terminated(&block[6..]).ok_or(PeError::Truncated)?;
+
+        // Text values are measured in UTF-16 code units.
+        let value_start = align4(6 + key.as_bytes().len() + 2).min(length);
+        let value_length = if is_text { value_length * 2 } else { value_length };
+        let value_end = (value_start + value_length).min(length);
+        let children = block.get(align4(value_end)..).unwrap_or_default();
+
+        let rest = data.get(align4(length)..).unwrap_or_default();
+        Ok((
+            VersionBlock {
+                key,
+                value: &block[value_start..value_end],
+                children,
+            },
+            rest,
+        ))
+    }
+
+    /// Parses all child blocks.
+    fn children(&self) -> Result<Vec<VersionBlock<'a>>, PeError> {
+        let mut children = Vec::new();
+        let mut data = self.children;
+        while !data.is_empty() {
+            let (child, rest) = VersionBlock::parse(data)?;
+            children.push(child);
+            data = rest;
+        }
+
+        Ok(children)
+    }
+}
+
+/// Rounds `x` up to a multiple of 4.
+fn align4(x: usize) -> usize {
+    x.next_multiple_of(4)
+}
+
+/// Parses a `StringTable` key, the language and code page as 8 hex digits, e.g. `040904b0`.
+fn parse_translation(key: Utf16Str) -> Option<(u16, u16)> {
+    if key.len() != 8 {
+        return None;
+    }
+
+    let value = key.chars().try_fold(0u32, |acc, x| Some(acc << 4 | x.to_digit(16)?))?;
+    Some(((value >> 16) as u16, value as u16))
+}
+
+impl<'a, W: PeWidth, const CHECKED: bool> PeHeaders<'a, W, CHECKED> {
+    /// Finds and decodes the version resource (`RT_VERSION`).
+    ///
+    /// # Arguments
+    /// - `language` - The language ID of the resource, or [`None`] for any language.
+    ///
+    /// # Remarks
+    /// The resource language usually does not matter, as a single version resource
+    /// holds a string table for each language it was translated to.
+    ///
+    /// # Returns
+    /// The version information, [`PeError::ResourceNotFound`] if there is no version
+    /// resource, or [`PeError::MalformedResource`] if it could not be decoded.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn version_info(&self, language: Option<u16>) -> Result<VersionInfo<'a>, PeError> {
+        let resource = self.resource(ResourceType::VERSION.into(), None, language)?;
+        let (root, _) = VersionBlock::parse(resource.data)?;
+        if root.key != "VS_VERSION_INFO" {
+            return Err(PeError::MalformedResource);
+        }
+
+        let fixed = match root.value.len() >= size_of::<VS_FIXEDFILEINFO>() {
+            // SAFETY: In bounds, and the read is unaligned.
+            true => Some(unsafe { read_unaligned(root.value.as_ptr() as *const VS_FIXEDFILEINFO) }),
+            false => None,
+        }
+        .filter(|x| x.signature() == VS_FFI_SIGNATURE);
+
+        let mut string_tables = Vec::new();
+        for string_file_info in root.children()?.iter().filter(|x| x.key == "StringFileInfo") {
+            for string_table in string_file_info.children()? {
+                let (language, code_page) = parse_translation(string_table.key).ok_or(PeError::MalformedResource)?;
+
+                // Some compilers count the value length in bytes, so stop at the terminator instead.
+                let strings = string_table
+                    .children()?
+                    .iter()
+                    .map(|x| {
+                        let value = Utf16Str::from_nul_terminated(x.value).map_or(Utf16Str::new(x.value), |x| x.0);
+                        (x.key, value)
+                    })
+                    .collect();
+
+                string_tables.push(VersionStringTable {
+                    language,
+                    code_page,
+                    strings,
+                });
+            }
+        }
+
+        Ok(VersionInfo { fixed, string_tables })
+    }
+}
+
+/// Finds and decodes the version resource (`RT_VERSION`).
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The version information, or [`None`] if it could not be read.
+/// Use [`try_get_version_info`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn get_version_info<'a, W: PeWidth>(pe_start: *const c_void, is_mapped: bool) -> Option<VersionInfo<'a>> {
+    try_get_version_info::<W>(pe_start, is_mapped).ok()
+}
+
+/// Finds and decodes the version resource (`RT_VERSION`).
+///
+/// # Arguments
+/// - `pe_start` - A pointer to the start of the PE file in memory.
+/// - `is_mapped` - A boolean indicating whether the PE file is mapped into memory.
+///
+/// # Safety
+/// We dajiobu if pe_start is a valid pointer and the PE file is valid.
+/// The PE file must remain valid for the lifetime `'a`.
+///
+/// # Returns
+/// The version information, [`PeError::ResourceNotFound`] if there is no version
+/// resource, or another [`PeError`] if the file is not a valid PE.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_get_version_info<'a, W: PeWidth>(
+    pe_start: *const c_void,
+    is_mapped: bool,
+) -> Result<VersionInfo<'a>, PeError> {
+    PeHeaders::<W>::from_ptr(pe_start, is_mapped)?.version_info(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::{AutoDetect, Pe32},
+        utils::{
+            map_image::map_image,
+            test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+        },
+    };
+
+    #[test]
+    fn test_version_info_x64() {
+        let info =
+            unsafe { get_version_info::<AutoDetect>(RELOADED_BOOTSTRAPPER_DLL_X64.as_ptr() as *const c_void, false) }
+                .unwrap();
+
+        let fixed = info.fixed.unwrap();
+        assert_eq!(fixed.file_version(), [7, 0, 0, 0]);
+        assert_eq!(fixed.product_version(), [7, 0, 0, 0]);
+
+        assert_eq!(info.string_tables.len(), 1);
+        assert_eq!(info.string_tables[0].language, 0x409);
+        assert_eq!(info.string_tables[0].code_page, 1200);
+        assert_eq!(info.string_tables[0].strings.len(), 7);
+        assert_eq!(info.string_tables[0].strings[0].0, "FileDescription");
+        assert_eq!(
+            info.string("FileDescription").unwrap(),
+            "C++ Bootstrapper to Load Reloaded II"
+        );
+        assert_eq!(info.string("FileVersion").unwrap(), "7");
+        assert_eq!(info.string("OriginalFilename").unwrap(), "Reloaded II Bootstrapper.dll");
+        assert_eq!(info.string("ProductName").unwrap(), "Reloaded II Bootstrapper");
+        assert_eq!(info.string("CompanyName"), None);
+    }
+
+    #[test]
+    fn test_version_info_x86() {
+        let view = PeView::new(RELOADED_BOOTSTRAPPER_DLL_X86, false).unwrap();
+        let info = view.version_info(Some(2057)).unwrap();
+        assert_eq!(info.string_tables[0].get("ProductVersion").unwrap(), "7");
+        assert_eq!(view.version_info(Some(0x409)), Err(PeError::ResourceNotFound));
+        assert_eq!(
+            unsafe { try_get_version_info::<Pe32>(view.pe_start() as *const c_void, false) }.map(|x| x.fixed.is_some()),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_version_info_malformed() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let view = PeView::new(&image, true).unwrap();
+        let data = view.resource(ResourceType::VERSION.into(), None, None).unwrap().rva as usize;
+
+        // Rewrite the DLL's version info in place, as a block claiming to be shorter than its own header.
+        image[data..data + 6].copy_from_slice(&[2, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            PeView::new(&image, true).unwrap().version_info(None),
+            Err(PeError::MalformedResource)
+        );
+
+        // Not a VS_VERSION_INFO block.
+        image[data..data + 10].copy_from_slice(b"\x0A\0\0\0\x01\0A\0\0\0");
+        assert_eq!(
+            PeView::new(&image, true).unwrap().version_info(None),
+            Err(PeError::MalformedResource)
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{
+    error::PeError, name_or_id::NameOrId, pe_headers::PeHeaders, pe_width::PeWidth,
+    utils::get_imports::ImportedFunction,
+};
+use alloc::vec::Vec;
+use core::{
+    ffi::c_void,
+    ptr::{read_unaligned, write_unaligned},
+};
+
+/// A slot in the import address table (IAT) of a mapped image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IatSlot {
+    /// Pointer to the slot. This is a `u64` for PE32+ and a `u32` for PE32.
+    pub ptr: *mut c_void,
+    /// The value of the slot when it was found, i.e. the address of the imported function.
+    pub original: u64,
+    /// Whether the slot is a `u64` (PE32+), rather than a `u32` (PE32).
+    pub is_pe64: bool,
+}
+
+impl IatSlot {
+    /// Creates a slot from the pointer to it, remembering the current value.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for reads of a `u64` if `is_pe64`, else a `u32`.
+    pub unsafe fn new(ptr: *mut c_void, is_pe64: bool) -> Self {
+        let original = if is_pe64 {
+            read_unaligned(ptr as *const u64)
+        } else {
+            read_unaligned(ptr as *const u32) as u64
+        };
+
+        Self { ptr, original, is_pe64 }
+    }
+
+    /// Writes a new address into the slot. For PE32, only the lower 32 bits are written.
+    ///
+    /// # Safety
+    /// The slot must be writable; the IAT is usually made read only by the loader,
+    /// so the page protection may need to be changed first.
+    pub unsafe fn write(&self, value: u64) {
+        if self.is_pe64 {
+            write_unaligned(self.ptr as *mut u64, value);
+        } else {
+            write_unaligned(self.ptr as *mut u32, value as u32);
+        }
+    }
+
+    /// Writes the original address back into the slot.
+    ///
+    /// # Safety
+    /// Same as [`IatSlot::write`].
+    pub unsafe fn restore(&self) {
+        self.write(self.original);
+    }
+}
+
+/// Finds the IAT slot of an imported function in a mapped image.
+///
+/// # Arguments
+/// - `image_base` - A pointer to the start of the mapped image.
+/// - `dll_name` - Name of the imported DLL, compared case insensitively.
+/// - `function` - Name or ordinal of the imported function.
+///
+/// # Remarks
+/// This function assumes that the PE file is valid and that the pointer is not null.
+///
+/// DLLs without an import lookup table (`original_first_thunk`) are skipped, since the
+/// IAT of a loaded image holds addresses, not names.
+///
+/// # Safety
+/// We dajiobu if image_base is a valid pointer to a mapped image.
+///
+/// # Returns
+/// The slot, or [`None`] if the function is not imported. Use [`try_find_iat_slot`] to find out why.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn find_iat_slot<W: PeWidth>(
+    image_base: *mut c_void,
+    dll_name: &str,
+    function: NameOrId<&str>,
+) -> Option<IatSlot> {
+    try_find_iat_slot::<W>(image_base, dll_name, function).ok()
+}
+
+/// Finds the IAT slot of an imported function in a mapped image.
+///
+/// # Arguments
+/// - `image_base` - A pointer to the start of the mapped image.
+/// - `dll_name` - Name of the imported DLL, compared case insensitively.
+/// - `function` - Name or ordinal of the imported function.
+///
+/// # Safety
+/// We dajiobu if image_base is a valid pointer to a mapped image.
+///
+/// # Returns
+/// The slot, or [`PeError::ImportNotFound`] if the function is not imported,
+/// or is imported from a DLL without an import lookup table.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn try_find_iat_slot<W: PeWidth>(
+    image_base: *mut c_void,
+    dll_name: &str,
+    function: NameOrId<&str>,
+) -> Result<IatSlot, PeError> {
+    let headers = PeHeaders::<W>::from_ptr(image_base, true)?;
+    let dll = headers
+        .resolved_imports()?
+        .into_iter()
+        .find(|x| x.name.eq_ignore_ascii_case(dll_name))
+        .ok_or(PeError::ImportNotFound)?;
+
+    let imported = dll
+        .functions
+        .iter()
+        .find(|x| x.name.matches(function))
+        .ok_or(PeError::ImportNotFound)?;
+
+    Ok(IatSlot::new(
+        (image_base as *mut u8).add(imported.iat_rva as usize) as *mut c_void,
+        headers.is_pe64(),
+    ))
+}
+
+/// Rewrites every IAT slot of a mapped image for which `replace` returns a new address.
+///
+/// # Arguments
+/// - `image_base` - A pointer to the start of the mapped image.
+/// - `replace` - Called with the DLL name, imported function and current slot value.
+///   Returns the new address for the slot, or [`None`] to leave it alone.
+///
+/// # Remarks
+/// DLLs without an import lookup table (`original_first_thunk`) are skipped, since the
+/// IAT of a loaded image holds addresses, not names.
+///
+/// # Safety
+/// We dajiobu if image_base is a valid pointer to a mapped image.
+/// The IAT must be writable, see [`IatSlot::write`].
+///
+/// # Returns
+/// The rewritten slots, with their original values, so they can be restored.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub unsafe fn hook_iat_slots<W: PeWidth>(
+    image_base: *mut c_void,
+    mut replace: impl FnMut(&str, &ImportedFunction, u64) -> Option<u64>,
+) -> Result<Vec<IatSlot>, PeError> {
+    let headers = PeHeaders::<W>::from_ptr(image_base, true)?;
+    let mut slots = Vec::new();
+    for dll in headers.resolved_imports()? {
+        for function in &dll.functions {
+            let slot = IatSlot::new(
+                (image_base as *mut u8).add(function.iat_rva as usize) as *mut c_void,
+                headers.is_pe64(),
+            );
+
+            if let Some(value) = replace(dll.name, function, slot.original) {
+                slot.write(value);
+                slots.push(slot);
+            }
+        }
+    }
+
+    Ok(slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pe_view::PeView,
+        pe_width::{AutoDetect, Pe32, Pe64},
+        prelude::DataDirectoryType,
+        utils::{
+            map_image::map_image,
+            test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+        },
+    };
+
+    /// Returns the RVA of the IAT slot of the given function, from the import table.
+    fn iat_rva(file: &[u8], dll: &str, function: NameOrId<&str>) -> usize {
+        let imports = PeView::new(file, false).unwrap().imports().unwrap();
+        let dll = imports.iter().find(|x| x.name.eq_ignore_ascii_case(dll)).unwrap();
+        dll.functions.iter().find(|x| x.name.matches(function)).unwrap().iat_rva as usize
+    }
+
+    #[test]
+    fn test_find_iat_slot_x64() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let image_base = image.as_mut_ptr() as *mut c_void;
+        let rva = iat_rva(
+            RELOADED_BOOTSTRAPPER_DLL_X64,
+            "kernel32.dll",
+            NameOrId::Name("GetCommandLineW"),
+        );
+
+        let slot =
+            unsafe { find_iat_slot::<Pe64>(image_base, "kernel32.dll", NameOrId::Name("GetCommandLineW")) }.unwrap();
+        assert_eq!(slot.ptr, unsafe { image_base.add(rva) });
+        assert!(slot.is_pe64);
+        // Not bound, so the IAT still has the RVA of the IMAGE_IMPORT_BY_NAME.
+        assert_eq!(slot.original, unsafe { *(slot.ptr as *const u64) });
+
+        unsafe { slot.write(0x1234_5678_9abc) };
+        assert_eq!(image[rva..rva + 8], 0x1234_5678_9abcu64.to_le_bytes());
+        unsafe { slot.restore() };
+        assert_eq!(image[rva..rva + 8], slot.original.to_le_bytes());
+
+        let image_base = image.as_mut_ptr() as *mut c_void;
+        assert_eq!(
+            unsafe { try_find_iat_slot::<AutoDetect>(image_base, "USER32.dll", NameOrId::Id(1)) },
+            Err(PeError::ImportNotFound)
+        );
+        assert_eq!(
+            unsafe { try_find_iat_slot::<AutoDetect>(image_base, "NOTUSER32.dll", NameOrId::Name("MessageBoxA")) },
+            Err(PeError::ImportNotFound)
+        );
+    }
+
+    #[test]
+    fn test_find_iat_slot_x86() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X86).unwrap();
+        let image_base = image.as_mut_ptr() as *mut c_void;
+        let rva = iat_rva(
+            RELOADED_BOOTSTRAPPER_DLL_X86,
+            "USER32.dll",
+            NameOrId::Name("MessageBoxA"),
+        );
+
+        let slot = unsafe { find_iat_slot::<Pe32>(image_base, "USER32.dll", NameOrId::Name("MessageBoxA")) }.unwrap();
+        assert_eq!(slot.ptr, unsafe { image_base.add(rva) });
+        assert!(!slot.is_pe64);
+
+        // Only the 4 byte slot is written.
+        let next = unsafe { *(image_base.add(rva + 4) as *const u32) };
+        unsafe { slot.write(0x1122_3344) };
+        assert_eq!(unsafe { *(slot.ptr as *const u32) }, 0x1122_3344);
+        assert_eq!(unsafe { *(image_base.add(rva + 4) as *const u32) }, next);
+    }
+
+    #[test]
+    fn test_hook_iat_slots() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let image_base = image.as_mut_ptr() as *mut c_void;
+
+        let slots = unsafe {
+            hook_iat_slots::<AutoDetect>(image_base, |dll, function, _| {
+                let is_target =
+                    dll == "KERNEL32.dll" && matches!(function.name, NameOrId::Name(x) if x.name.starts_with("Get"));
+                is_target.then_some(0xDEAD_BEEF)
+            })
+        }
+        .unwrap();
+
+        assert!(!slots.is_empty());
+        for slot in &slots {
+            assert_eq!(unsafe { *(slot.ptr as *const u64) }, 0xDEAD_BEEF);
+            unsafe { slot.restore() };
+        }
+
+        assert_eq!(
+            unsafe { find_iat_slot::<AutoDetect>(image_base, "KERNEL32.dll", NameOrId::Name("GetCommandLineW")) }
+                .unwrap()
+                .original,
+            slots[0].original
+        );
+    }
+
+    #[test]
+    fn test_iat_without_lookup_table() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+
+        // Resolve KERNEL32's imports to addresses, and drop its lookup table.
+        let view = PeView::new(RELOADED_BOOTSTRAPPER_DLL_X64, false).unwrap();
+        let descriptor = view
+            .data_directory(DataDirectoryType::ImportTable)
+            .unwrap()
+            .virtual_address() as usize;
+        let kernel32 = view.imports().unwrap().remove(0);
+        let other_dll = view.imports().unwrap()[1].name;
+        image[descriptor..descriptor + 4].fill(0);
+        for function in &kernel32.functions {
+            let slot = function.iat_rva as usize;
+            image[slot..slot + 8].copy_from_slice(&0x7ff8_1234_5678u64.to_le_bytes());
+        }
+
+        let image_base = image.as_mut_ptr() as *mut c_void;
+        assert_eq!(
+            unsafe { try_find_iat_slot::<AutoDetect>(image_base, "KERNEL32.dll", NameOrId::Name("GetCommandLineW")) },
+            Err(PeError::ImportNotFound)
+        );
+
+        let (mut seen_kernel32, mut seen_other_dll) = (false, false);
+        let slots = unsafe {
+            hook_iat_slots::<AutoDetect>(image_base, |dll, _, _| {
+                seen_kernel32 |= dll == "KERNEL32.dll";
+                seen_other_dll |= dll == other_dll;
+                None
+            })
+        }
+        .unwrap();
+        assert!(slots.is_empty());
+        assert!(!seen_kernel32);
+        assert!(seen_other_dll);
+    }
+}
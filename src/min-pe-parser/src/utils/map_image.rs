@@ -0,0 +1,110 @@
+use crate::{
+    error::PeError, pe_headers::PeHeaders, pe_view::PeView, pe_width::PeWidth, utils::aligned_buffer::AlignedBuffer,
+};
+
+impl<W: PeWidth, const CHECKED: bool> PeHeaders<'_, W, CHECKED> {
+    /// Copies the PE file into the mapped (image) layout, as the loader would, without
+    /// applying relocations or resolving imports.
+    ///
+    /// # Remarks
+    /// Each section's raw data is placed at its virtual address, and the remainder of
+    /// its virtual size is left zero filled. Files that are already mapped are copied as is.
+    ///
+    /// # Returns
+    /// A buffer of `size_of_image` bytes, to be parsed with `is_mapped = true`.
+    /// [`PeError::Truncated`] if a section's raw data is outside the file, or its
+    /// virtual address is outside the image.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn map_image(&self) -> Result<AlignedBuffer, PeError> {
+        let size_of_image = self.size_of_image() as usize;
+        if self.is_mapped() {
+            return Ok(AlignedBuffer::from(self.bytes(0, size_of_image)?));
+        }
+
+        let mut image = AlignedBuffer::new(size_of_image);
+        let size_of_headers = (self.size_of_headers() as usize).min(size_of_image);
+        image[..size_of_headers].copy_from_slice(self.bytes(0, size_of_headers)?);
+
+        for section in self.section_headers() {
+            // Some linkers leave the virtual size as 0.
+            let virtual_size = match section.virtual_size() {
+                0 => section.size_of_raw_data(),
+                x => x,
+            };
+
+            let size = section.size_of_raw_data().min(virtual_size) as usize;
+            let data = self.bytes(section.pointer_to_raw_data() as usize, size)?;
+            let va = section.virtual_address() as usize;
+            image
+                .get_mut(va..va.saturating_add(size))
+                .ok_or(PeError::Truncated)?
+                .copy_from_slice(data);
+        }
+
+        Ok(image)
+    }
+}
+
+/// Copies a PE file into the mapped (image) layout, as the loader would, without
+/// applying relocations or resolving imports.
+///
+/// # Arguments
+/// - `file` - The PE file, in the file layout. Must be aligned to 4 bytes.
+///
+/// # Returns
+/// A buffer of `size_of_image` bytes, to be parsed with `is_mapped = true`, or a
+/// [`PeError`] if the file is malformed.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub fn map_image(file: &[u8]) -> Result<AlignedBuffer, PeError> {
+    PeView::new(file, false)?.map_image()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86};
+
+    #[test]
+    fn test_map_image_x64() {
+        let image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let view = PeView::new(&image, true).unwrap();
+        assert_eq!(image.len(), view.size_of_image() as usize);
+        assert_eq!(&image[..0x400], &RELOADED_BOOTSTRAPPER_DLL_X64[..0x400]);
+
+        // Same results as the file layout.
+        let file = PeView::new(RELOADED_BOOTSTRAPPER_DLL_X64, false).unwrap();
+        assert_eq!(view.export_rva("InitializeASI"), Ok(0x236c));
+        assert_eq!(view.imports(), file.imports());
+        assert_eq!(view.exports().unwrap().count(), 8);
+
+        // The tail of each section past its raw data is zero.
+        for section in view.section_headers() {
+            let va = section.virtual_address() as usize;
+            let end = va + section.virtual_size() as usize;
+            assert!(image[(va + section.size_of_raw_data() as usize).min(end)..end]
+                .iter()
+                .all(|&x| x == 0));
+        }
+
+        // Mapping a mapped image copies it.
+        assert_eq!(view.map_image(), Ok(image.clone()));
+    }
+
+    #[test]
+    fn test_map_image_x86() {
+        let image = map_image(RELOADED_BOOTSTRAPPER_DLL_X86).unwrap();
+        assert_eq!(image.len(), 0x20000);
+        assert_eq!(
+            PeView::new(&image, true).unwrap().export_rva("InitializeASI"),
+            Ok(0x1caa)
+        );
+    }
+
+    #[test]
+    fn test_map_image_truncated() {
+        assert_eq!(
+            map_image(&RELOADED_BOOTSTRAPPER_DLL_X64[..0x1000]),
+            Err(PeError::Truncated)
+        );
+    }
+}
@@ -22,6 +22,9 @@ mod macros {
     }
 }
 
+use crate::{name_or_id::NameOrId, pe_view::PeView, prelude::*, utils::aligned_buffer::AlignedBuffer};
+use alloc::vec::Vec;
+
 #[repr(align(4))]
 struct Align4;
 
@@ -33,3 +36,96 @@ pub const RELOADED_BOOTSTRAPPER_DLL_X86: &[u8] = include_bytes_align_as!(
     Align4,
     "../../assets/test_data/x86/Reloaded.Mod.Loader.Bootstrapper.dll"
 );
+
+/// Returns the offset of the given data directory's entry in the optional header.
+pub fn directory_entry(image: &[u8], directory: DataDirectoryType) -> usize {
+    let view = PeView::new(image, false).unwrap();
+    view.data_directories()[directory as usize..].as_ptr() as usize - view.pe_start() as usize
+}
+
+/// Appends a table to a mapped image, 8 byte aligned, and points the given data directory at it,
+/// replacing the existing one. `table` is called with the RVA of the table, so the table can refer
+/// to itself. Returns the RVA of the table.
+pub fn add_table(image: &mut AlignedBuffer, directory: DataDirectoryType, table: impl FnOnce(u32) -> Vec<u8>) -> u32 {
+    let rva = image.len().next_multiple_of(8);
+    let table = table(rva as u32);
+    image.resize(rva + table.len());
+    image[rva..].copy_from_slice(&table);
+
+    let entry = directory_entry(image, directory);
+    image[entry..entry + 8].copy_from_slice(&[rva as u32, table.len() as u32].map(u32::to_le_bytes).concat());
+    rva as u32
+}
+
+/// Encodes a string as UTF-16LE, prefixed with its length in code units, as in resource names
+/// and string tables.
+pub fn counted_utf16(text: &str) -> Vec<u8> {
+    let units: Vec<u16> = text.encode_utf16().collect();
+    [
+        (units.len() as u16).to_le_bytes().to_vec(),
+        units.iter().flat_map(|x| x.to_le_bytes()).collect(),
+    ]
+    .concat()
+}
+
+/// A resource for [`add_resources`]: type, name, language and data.
+pub type TestResource<'a> = (NameOrId<&'a str>, NameOrId<&'a str>, u16, &'a [u8]);
+
+/// Appends a resource directory holding `resources` to a mapped image, replacing its existing one.
+/// Resources with the same type, and with the same name, must be adjacent.
+pub fn add_resources(image: &mut AlignedBuffer, resources: &[TestResource]) {
+    let resources: Vec<_> = resources
+        .iter()
+        .map(|&(ty, name, language, data)| ([ty, name, NameOrId::Id(language)], data))
+        .collect();
+
+    add_table(image, DataDirectoryType::ResourceTable, |rva| {
+        let mut tree = Vec::new();
+        add_resource_directory(&mut tree, &resources, 0, rva as usize);
+        tree
+    });
+}
+
+/// Writes the directory for `level` (type, name or language) of the tree, then its children.
+fn add_resource_directory(tree: &mut Vec<u8>, resources: &[([NameOrId<&str>; 3], &[u8])], level: usize, table: usize) {
+    let groups: Vec<_> = resources.chunk_by(|a, b| a.0[level] == b.0[level]).collect();
+    let named = groups
+        .iter()
+        .filter(|x| matches!(x[0].0[level], NameOrId::Name(_)))
+        .count() as u16;
+    let entries = tree.len() + 16;
+    tree.extend_from_slice(&[0; 12]);
+    tree.extend_from_slice(&named.to_le_bytes());
+    tree.extend_from_slice(&(groups.len() as u16 - named).to_le_bytes());
+    tree.resize(entries + groups.len() * 8, 0);
+
+    for (x, group) in groups.iter().enumerate() {
+        let id = match group[0].0[level] {
+            NameOrId::Id(id) => id as u32,
+            NameOrId::Name(name) => {
+                let offset = IMAGE_RESOURCE_NAME_IS_STRING | tree.len() as u32;
+                tree.extend_from_slice(&counted_utf16(name));
+                offset
+            }
+        };
+
+        tree.resize(tree.len().next_multiple_of(4), 0);
+        let offset = tree.len() as u32;
+        let offset = match level {
+            2 => {
+                let data = group[0].1;
+                let rva = (table + tree.len() + 16) as u32;
+                tree.extend_from_slice(&[rva, data.len() as u32, 0, 0].map(u32::to_le_bytes).concat());
+                tree.extend_from_slice(data);
+                offset
+            }
+            _ => {
+                add_resource_directory(tree, group, level + 1, table);
+                IMAGE_RESOURCE_DATA_IS_DIRECTORY | offset
+            }
+        };
+
+        let entry = entries + x * 8;
+        tree[entry..entry + 8].copy_from_slice(&[id.to_le_bytes(), offset.to_le_bytes()].concat());
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{
+    error::PeError, pe_headers::PeHeaders, pe_view::PeView, pe_width::PeWidth, prelude::*,
+    utils::aligned_buffer::AlignedBuffer,
+};
+use alloc::vec::Vec;
+use core::mem::size_of;
+
+impl<W: PeWidth, const CHECKED: bool> PeHeaders<'_, W, CHECKED> {
+    /// Rebuilds a file layout PE from the image, e.g. for dumping a module loaded in memory.
+    ///
+    /// # Arguments
+    /// - `fix_raw_pointers` - Lay the sections out again, back to back from the end of the
+    ///   headers, with each `size_of_raw_data` covering the whole virtual size rounded up to
+    ///   `file_alignment`. Use this when sections grew in memory (e.g. uninitialized data,
+    ///   or data written at runtime), which would otherwise be cut off.
+    ///
+    /// # Remarks
+    /// Without `fix_raw_pointers`, each section is copied to its existing `pointer_to_raw_data`,
+    /// truncated to its existing `size_of_raw_data`. With it, the section headers in the output
+    /// are updated to match the new layout.
+    ///
+    /// A file that is not mapped is mapped first, with [`PeHeaders::map_image`].
+    ///
+    /// # Returns
+    /// The file, to be parsed with `is_mapped = false`, or a [`PeError`] if the image is malformed.
+    #[cfg_attr(feature = "size_opt", optimize(size))]
+    pub fn unmap_image(&self, fix_raw_pointers: bool) -> Result<AlignedBuffer, PeError> {
+        if !self.is_mapped() {
+            let image = self.map_image()?;
+            return PeView::new(&image, true)?.unmap_image(fix_raw_pointers);
+        }
+
+        let size_of_image = self.size_of_image() as usize;
+        let size_of_headers = (self.size_of_headers() as usize).min(size_of_image);
+        let file_alignment = self.file_alignment().max(1);
+
+        // The whole image must be present; it also bounds the size of the output, below.
+        self.bytes(0, size_of_image)?;
+
+        // Work out where each section goes.
+        let mut sections: Vec<IMAGE_SECTION_HEADER> = self.section_headers().to_vec();
+        if fix_raw_pointers {
+            let mut pointer = (size_of_headers as u32)
+                .checked_next_multiple_of(file_alignment)
+                .ok_or(PeError::Truncated)?;
+
+            for section in sections.iter_mut() {
+                let virtual_size = match section.virtual_size() {
+                    0 => section.size_of_raw_data(),
+                    x => x,
+                };
+
+                let size = virtual_size
+                    .checked_next_multiple_of(file_alignment)
+                    .ok_or(PeError::Truncated)?;
+                section.set_pointer_to_raw_data(if size == 0 { 0 } else { pointer });
+                section.set_size_of_raw_data(size);
+                pointer = pointer.checked_add(size).ok_or(PeError::Truncated)?;
+            }
+        }
+
+        let len = sections.iter().try_fold(size_of_headers, |len, x| {
+            let end = (x.pointer_to_raw_data() as usize).checked_add(x.size_of_raw_data() as usize)?;
+            Some(len.max(end))
+        });
+
+        // The raw data of a valid file never takes more room than the image does in memory,
+        // so don't let a hostile header allocate more than that.
+        let len = len.filter(|&x| x <= size_of_image).ok_or(PeError::Truncated)?;
+
+        let mut file = AlignedBuffer::new(len);
+        file[..size_of_headers].copy_from_slice(self.bytes(0, size_of_headers)?);
+
+        for section in sections.iter() {
+            // Anything past the end of the image is left zero filled.
+            let va = section.virtual_address() as usize;
+            let size = (section.size_of_raw_data() as usize).min(size_of_image.saturating_sub(va));
+            let raw = section.pointer_to_raw_data() as usize;
+            file[raw..raw + size].copy_from_slice(self.bytes(va, size)?);
+        }
+
+        if fix_raw_pointers {
+            let section_headers = self.section_headers().as_ptr() as usize - self.pe_start() as usize;
+            let end = section_headers + sections.len() * size_of::<IMAGE_SECTION_HEADER>();
+            let headers = file.get_mut(section_headers..end).ok_or(PeError::Truncated)?;
+
+            // SAFETY: In bounds, and the write is unaligned.
+            unsafe {
+                (headers.as_mut_ptr() as *mut IMAGE_SECTION_HEADER)
+                    .copy_from_nonoverlapping(sections.as_ptr(), sections.len())
+            };
+        }
+
+        Ok(file)
+    }
+}
+
+/// Rebuilds a file layout PE from a mapped image, e.g. for dumping a module loaded in memory.
+///
+/// # Arguments
+/// - `image` - The PE file, in the mapped (image) layout. Must be aligned to 4 bytes.
+/// - `fix_raw_pointers` - Lay the sections out again, so that sections which grew in
+///   memory are not cut off. See [`PeHeaders::unmap_image`].
+///
+/// # Returns
+/// The file, to be parsed with `is_mapped = false`, or a [`PeError`] if the image is malformed.
+#[cfg_attr(feature = "size_opt", optimize(size))]
+pub fn unmap_image(image: &[u8], fix_raw_pointers: bool) -> Result<AlignedBuffer, PeError> {
+    PeView::new(image, true)?.unmap_image(fix_raw_pointers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        map_image::map_image,
+        test_utils::{RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86},
+    };
+    use core::mem::offset_of;
+
+    #[test]
+    fn test_unmap_image_round_trip() {
+        for file in [RELOADED_BOOTSTRAPPER_DLL_X64, RELOADED_BOOTSTRAPPER_DLL_X86] {
+            let image = map_image(file).unwrap();
+            assert_eq!(&unmap_image(&image, false).unwrap()[..], file);
+        }
+    }
+
+    #[test]
+    fn test_unmap_image_fix_raw_pointers() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let original = PeView::new(RELOADED_BOOTSTRAPPER_DLL_X64, false)
+            .unwrap()
+            .section_headers()[2];
+        let raw = original.pointer_to_raw_data() as usize;
+        let past = original.size_of_raw_data() as usize + 0x100;
+        assert!(original.virtual_size() as usize > past);
+
+        // Data written at runtime, past the raw data of `.data`.
+        image[original.virtual_address() as usize + past] = 0xAA;
+
+        let file = unmap_image(&image, true).unwrap();
+        let view = PeView::new(&file, false).unwrap();
+        let data = &view.section_headers()[2];
+        assert_eq!(data.pointer_to_raw_data() as usize, raw);
+        assert!(data.size_of_raw_data() as usize > past);
+        assert!(data.size_of_raw_data().is_multiple_of(view.file_alignment()));
+        assert_eq!(file[raw + past], 0xAA);
+
+        // The later sections were moved, and the file still parses.
+        assert_eq!(file.len(), {
+            let last = view.section_headers().last().unwrap();
+            (last.pointer_to_raw_data() + last.size_of_raw_data()) as usize
+        });
+        assert_eq!(view.export_rva("InitializeASI"), Ok(0x236c));
+        assert_eq!(view.imports(), PeView::new(&image, true).unwrap().imports());
+        // Only the section headers differ, once mapped again.
+        let headers = view.size_of_headers() as usize;
+        assert_eq!(map_image(&file).unwrap()[headers..], image[headers..]);
+
+        // The same result, starting from the file layout.
+        assert_eq!(
+            PeView::new(RELOADED_BOOTSTRAPPER_DLL_X64, false)
+                .unwrap()
+                .unmap_image(true)
+                .unwrap()[..raw],
+            file[..raw]
+        );
+    }
+
+    #[test]
+    fn test_unmap_image_hostile_sizes() {
+        let mut image = map_image(RELOADED_BOOTSTRAPPER_DLL_X64).unwrap();
+        let section = {
+            let view = PeView::new(&image, true).unwrap();
+            view.section_headers().as_ptr() as usize - view.pe_start() as usize
+        };
+        let set = |image: &mut AlignedBuffer, field: usize, value: u32| {
+            image[section + field..section + field + 4].copy_from_slice(&value.to_le_bytes());
+        };
+
+        // Raw data far past the end of the image; would allocate 4GB.
+        set(
+            &mut image,
+            offset_of!(IMAGE_SECTION_HEADER, size_of_raw_data),
+            0xFFFF_0000,
+        );
+        assert_eq!(unmap_image(&image, false), Err(PeError::Truncated));
+
+        // A virtual size which overflows once aligned.
+        set(&mut image, offset_of!(IMAGE_SECTION_HEADER, virtual_size), 0xFFFF_FFFF);
+        assert_eq!(unmap_image(&image, true), Err(PeError::Truncated));
+    }
+}